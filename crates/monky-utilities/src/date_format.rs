@@ -23,9 +23,10 @@
 
 use time::format_description::well_known::Rfc3339;
 use time::format_description::FormatItem;
-use time::{format_description, OffsetDateTime};
+use time::{format_description, OffsetDateTime, UtcOffset};
 
 use std::fmt;
+use std::time::Duration;
 
 /// Errors for date formatting and parsing without external crates.
 #[derive(Debug)]
@@ -40,6 +41,10 @@ pub enum DateFormatError {
     IntConversion,
     /// Raised when Unix timestamp is invalid or out of range.
     InvalidTimestamp,
+    /// Raised when a duration string has a malformed token or unknown unit.
+    InvalidDuration(String),
+    /// Raised when `IsoFormatOptions::fractional_digits` is not one of 0, 3, 6, or 9.
+    InvalidFractionalDigits(u8),
 }
 
 impl fmt::Display for DateFormatError {
@@ -54,6 +59,12 @@ impl fmt::Display for DateFormatError {
                 write!(f, "integer conversion overflow or underflow")
             }
             DateFormatError::InvalidTimestamp => write!(f, "invalid unix timestamp"),
+            DateFormatError::InvalidDuration(token) => {
+                write!(f, "invalid duration token: {}", token)
+            }
+            DateFormatError::InvalidFractionalDigits(n) => {
+                write!(f, "invalid fractional digits: {} (expected 0, 3, 6, or 9)", n)
+            }
         }
     }
 }
@@ -90,6 +101,46 @@ fn seconds_format_description() -> Result<Vec<FormatItem<'static>>, time::error:
 }
 
 
+/// How `iso_from_millis_with` renders the timestamp's offset suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsoOffset {
+    /// Render a trailing `Z` (Zulu/UTC).
+    Utc,
+    /// Shift the timestamp to the given `UtcOffset` and render its numeric
+    /// offset, e.g. `+05:30`.
+    Explicit(UtcOffset),
+}
+
+/// Options controlling `iso_from_millis_with`'s output.
+#[derive(Debug, Clone)]
+pub struct IsoFormatOptions {
+    /// Number of fractional-second digits to emit: 0, 3, 6, or 9.
+    pub fractional_digits: u8,
+    /// Whether to render a trailing `Z` or an explicit numeric offset.
+    pub offset: IsoOffset,
+    /// Optional caller-supplied `time::format_description` pattern,
+    /// overriding the built-in layout entirely (fractional_digits is then
+    /// ignored; an `Explicit` offset still shifts the date/time before the
+    /// pattern is applied).
+    pub pattern: Option<String>,
+}
+
+impl Default for IsoFormatOptions {
+    fn default() -> Self {
+        IsoFormatOptions {
+            fractional_digits: 3,
+            offset: IsoOffset::Utc,
+            pattern: None,
+        }
+    }
+}
+
+impl IsoFormatOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
 /// Converts epoch milliseconds into an ISO 8601 string with exactly three
 /// fractional digits and a trailing `Z`.
 ///
@@ -106,26 +157,71 @@ fn seconds_format_description() -> Result<Vec<FormatItem<'static>>, time::error:
 /// * `DateFormatError::InvalidTimestamp` if timestamp is out of range.
 /// * `DateFormatError::Format` if formatting fails.
 pub fn iso_from_millis(epoch_millis: i128) -> Result<String, DateFormatError> {
+    iso_from_millis_with(epoch_millis, &IsoFormatOptions::default())
+}
+
+/// Converts epoch milliseconds into an ISO 8601 string per `opts`, allowing
+/// configurable fractional-second precision, a `Z` vs explicit numeric
+/// offset suffix, or an entirely caller-supplied format pattern.
+///
+/// # Errors
+///
+/// * `DateFormatError::IntConversion` if multiplication overflows.
+/// * `DateFormatError::InvalidTimestamp` if timestamp is out of range.
+/// * `DateFormatError::InvalidFormatDescription` if `opts.pattern` fails to parse.
+/// * `DateFormatError::InvalidFractionalDigits` if `opts.fractional_digits` isn't 0, 3, 6, or 9.
+/// * `DateFormatError::Format` if formatting fails.
+pub fn iso_from_millis_with(
+    epoch_millis: i128,
+    opts: &IsoFormatOptions,
+) -> Result<String, DateFormatError> {
     // Convert millis -> nanos safely
     let nanos = epoch_millis
         .checked_mul(1_000_000)
         .ok_or(DateFormatError::IntConversion)?;
 
-    // Build OffsetDateTime from nanos (may fail if out of range)
+    // Build OffsetDateTime from nanos (may fail if out of range), then shift
+    // it to the options' target offset before formatting — otherwise
+    // `IsoOffset::Explicit` would render the right suffix but the wrong
+    // (still-UTC) date/time.
     let odt =
         OffsetDateTime::from_unix_timestamp_nanos(nanos).map_err(|_| DateFormatError::InvalidTimestamp)?;
+    let odt = match opts.offset {
+        IsoOffset::Utc => odt,
+        IsoOffset::Explicit(target) => odt.to_offset(target),
+    };
+
+    if let Some(pattern) = &opts.pattern {
+        let fmt = format_description::parse(pattern)?;
+        return Ok(odt.format(&fmt)?);
+    }
 
     // Format base (without fractional seconds)
     let fmt = seconds_format_description()?;
     let base = odt.format(&fmt)?; // e.g. "2020-09-12T12:34:16"
 
-    // Compute millisecond component in range 0..=999 robustly for negative timestamps too.
-    // rem_euclid yields non-negative remainder.
-    let ms = epoch_millis.rem_euclid(1000) as i128; // 0..=999
+    // rem_euclid yields non-negative remainder for negative timestamps too.
+    // The fractional part of a second is unaffected by shifting offsets.
+    let nanos_in_sec = nanos.rem_euclid(1_000_000_000);
+    let fractional = match opts.fractional_digits {
+        0 => String::new(),
+        3 => format!(".{:03}", nanos_in_sec / 1_000_000),
+        6 => format!(".{:06}", nanos_in_sec / 1_000),
+        9 => format!(".{:09}", nanos_in_sec),
+        other => return Err(DateFormatError::InvalidFractionalDigits(other)),
+    };
+
+    let suffix = match opts.offset {
+        IsoOffset::Utc => "Z".to_string(),
+        IsoOffset::Explicit(_) => {
+            let total_seconds = odt.offset().whole_seconds();
+            let sign = if total_seconds < 0 { '-' } else { '+' };
+            let abs_secs = total_seconds.unsigned_abs();
+            format!("{}{:02}:{:02}", sign, abs_secs / 3600, (abs_secs % 3600) / 60)
+        }
+    };
 
-    // Assemble final string ensuring 3-digit zero-padded millis and trailing Z
-    let result = format!("{}.{:03}Z", base, ms);
-    Ok(result)
+    Ok(format!("{}{}{}", base, fractional, suffix))
 }
 
 /// Parses an ISO/RFC3339 date-time string into epoch milliseconds and
@@ -153,6 +249,120 @@ pub fn instant_from_iso(iso_str: &str) -> Result<(i128, OffsetDateTime), DateFor
     Ok((millis, odt))
 }
 
+/// Resolves a duration unit suffix (e.g. `"h"`, `"day"`, `"month"`) to its
+/// length in nanoseconds, accepting a trailing `s` for plural forms
+/// (`"days"`, `"years"`, ...).
+fn duration_unit_nanos(unit: &str) -> Option<u64> {
+    const NS: u64 = 1;
+    const US: u64 = 1_000;
+    const MS: u64 = 1_000_000;
+    const SEC: u64 = 1_000_000_000;
+    const MIN: u64 = 60 * SEC;
+    const HOUR: u64 = 60 * MIN;
+    const DAY: u64 = 24 * HOUR;
+    const WEEK: u64 = 7 * DAY;
+    const MONTH: u64 = 30 * DAY;
+    const YEAR: u64 = 365 * DAY;
+
+    fn resolve(unit: &str) -> Option<u64> {
+        Some(match unit {
+            "ns" => NS,
+            "us" | "µs" => US,
+            "ms" => MS,
+            "s" | "sec" => SEC,
+            "m" | "min" => MIN,
+            "h" | "hr" => HOUR,
+            "d" | "day" => DAY,
+            "w" | "week" => WEEK,
+            "month" => MONTH,
+            "year" => YEAR,
+            _ => return None,
+        })
+    }
+
+    resolve(unit).or_else(|| unit.strip_suffix('s').and_then(resolve))
+}
+
+/// Parses a whitespace-separated list of `<number><unit>` tokens (e.g.
+/// `"1h 30min"`, `"2years 1month 5days"`, `"500ms"`) into a `Duration`,
+/// summing all tokens together.
+///
+/// Supported units: `ns`, `us`/`µs`, `ms`, `s`/`sec`, `m`/`min`, `h`/`hr`,
+/// `d`/`day`, `w`/`week`, `month` (30 days), `year` (365 days) — each also
+/// accepting a trailing `s` for the plural form.
+///
+/// # Errors
+///
+/// * `DateFormatError::InvalidDuration` if a token is malformed or uses an
+///   unrecognized unit.
+/// * `DateFormatError::IntConversion` if the accumulated total overflows.
+pub fn parse_duration(s: &str) -> Result<Duration, DateFormatError> {
+    let mut total_nanos: u64 = 0;
+
+    for token in s.split_whitespace() {
+        let split_at = token
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| DateFormatError::InvalidDuration(token.to_string()))?;
+        let (number_part, unit_part) = token.split_at(split_at);
+        if number_part.is_empty() {
+            return Err(DateFormatError::InvalidDuration(token.to_string()));
+        }
+
+        let value: u64 = number_part
+            .parse()
+            .map_err(|_| DateFormatError::InvalidDuration(token.to_string()))?;
+        let unit_nanos = duration_unit_nanos(unit_part)
+            .ok_or_else(|| DateFormatError::InvalidDuration(token.to_string()))?;
+
+        let token_nanos = value
+            .checked_mul(unit_nanos)
+            .ok_or(DateFormatError::IntConversion)?;
+        total_nanos = total_nanos
+            .checked_add(token_nanos)
+            .ok_or(DateFormatError::IntConversion)?;
+    }
+
+    Ok(Duration::from_nanos(total_nanos))
+}
+
+/// Formats a `Duration` as a largest-unit-first canonical string (e.g.
+/// `"1h 30m"`), skipping zero components. Returns `"0s"` for a zero
+/// duration.
+///
+/// Calendar-approximate units (`month`, `year`) accepted by `parse_duration`
+/// are intentionally not re-derived here, since their length is ambiguous;
+/// the largest unit emitted is weeks.
+pub fn format_duration(d: Duration) -> String {
+    let mut remaining_nanos = d.as_nanos();
+    if remaining_nanos == 0 {
+        return "0s".to_string();
+    }
+
+    const UNITS: [(&str, u128); 7] = [
+        ("w", 604_800_000_000_000),
+        ("d", 86_400_000_000_000),
+        ("h", 3_600_000_000_000),
+        ("m", 60_000_000_000),
+        ("s", 1_000_000_000),
+        ("ms", 1_000_000),
+        ("us", 1_000),
+    ];
+
+    let mut parts = Vec::new();
+    for (suffix, unit_nanos) in UNITS {
+        let count = remaining_nanos / unit_nanos;
+        if count > 0 {
+            parts.push(format!("{}{}", count, suffix));
+            remaining_nanos -= count * unit_nanos;
+        }
+    }
+    if remaining_nanos > 0 {
+        parts.push(format!("{}ns", remaining_nanos));
+    }
+
+    parts.join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -223,4 +433,162 @@ mod tests {
         // Allow 1 ms rounding tolerance
         assert!((now - millis_back).abs() <= 1);
     }
+
+    #[test]
+    fn test_iso_from_millis_with_zero_fractional_digits() {
+        let opts = IsoFormatOptions {
+            fractional_digits: 0,
+            ..IsoFormatOptions::default()
+        };
+        let iso = iso_from_millis_with(1_602_123_456_789, &opts).unwrap();
+        assert_eq!(iso, "2020-10-08T02:17:36Z");
+    }
+
+    #[test]
+    fn test_iso_from_millis_with_nine_fractional_digits() {
+        let opts = IsoFormatOptions {
+            fractional_digits: 9,
+            ..IsoFormatOptions::default()
+        };
+        let iso = iso_from_millis_with(1_602_123_456_789, &opts).unwrap();
+        assert_eq!(iso, "2020-10-08T02:17:36.789000000Z");
+    }
+
+    #[test]
+    fn test_iso_from_millis_with_explicit_offset_utc() {
+        let opts = IsoFormatOptions {
+            offset: IsoOffset::Explicit(UtcOffset::UTC),
+            ..IsoFormatOptions::default()
+        };
+        let iso = iso_from_millis_with(1_602_123_456_789, &opts).unwrap();
+        assert_eq!(iso, "2020-10-08T02:17:36.789+00:00");
+    }
+
+    #[test]
+    fn test_iso_from_millis_with_explicit_offset_shifts_date_time() {
+        // 2020-10-08T02:17:36.789Z shifted to +05:30 becomes 07:47:36.789.
+        let opts = IsoFormatOptions {
+            offset: IsoOffset::Explicit(UtcOffset::from_hms(5, 30, 0).unwrap()),
+            ..IsoFormatOptions::default()
+        };
+        let iso = iso_from_millis_with(1_602_123_456_789, &opts).unwrap();
+        assert_eq!(iso, "2020-10-08T07:47:36.789+05:30");
+    }
+
+    #[test]
+    fn test_iso_from_millis_with_explicit_negative_offset() {
+        // 2020-10-08T02:17:36.789Z shifted to -04:00 becomes 2020-10-07T22:17:36.789.
+        let opts = IsoFormatOptions {
+            offset: IsoOffset::Explicit(UtcOffset::from_hms(-4, 0, 0).unwrap()),
+            ..IsoFormatOptions::default()
+        };
+        let iso = iso_from_millis_with(1_602_123_456_789, &opts).unwrap();
+        assert_eq!(iso, "2020-10-07T22:17:36.789-04:00");
+    }
+
+    #[test]
+    fn test_iso_from_millis_with_rejects_invalid_fractional_digits() {
+        let opts = IsoFormatOptions {
+            fractional_digits: 4,
+            ..IsoFormatOptions::default()
+        };
+        let result = iso_from_millis_with(1_602_123_456_789, &opts);
+        assert!(matches!(
+            result,
+            Err(DateFormatError::InvalidFractionalDigits(4))
+        ));
+    }
+
+    #[test]
+    fn test_iso_from_millis_with_custom_pattern() {
+        let opts = IsoFormatOptions {
+            pattern: Some("[year]/[month]/[day]".to_string()),
+            ..IsoFormatOptions::default()
+        };
+        let iso = iso_from_millis_with(1_602_123_456_789, &opts).unwrap();
+        assert_eq!(iso, "2020/10/08");
+    }
+
+    #[test]
+    fn test_iso_from_millis_with_invalid_pattern() {
+        let opts = IsoFormatOptions {
+            pattern: Some("[bogus]".to_string()),
+            ..IsoFormatOptions::default()
+        };
+        let result = iso_from_millis_with(1_602_123_456_789, &opts);
+        assert!(matches!(
+            result,
+            Err(DateFormatError::InvalidFormatDescription(_))
+        ));
+    }
+
+    #[test]
+    fn test_iso_from_millis_matches_default_options() {
+        let direct = iso_from_millis(1_602_123_456_789).unwrap();
+        let via_opts = iso_from_millis_with(1_602_123_456_789, &IsoFormatOptions::default()).unwrap();
+        assert_eq!(direct, via_opts);
+    }
+
+    #[test]
+    fn test_parse_duration_sums_multiple_tokens() {
+        let d = parse_duration("1h 30min").unwrap();
+        assert_eq!(d, Duration::from_secs(90 * 60));
+    }
+
+    #[test]
+    fn test_parse_duration_accepts_plural_calendar_units() {
+        let d = parse_duration("2years 1month 5days").unwrap();
+        let expected_secs = 2 * 365 * 86_400 + 30 * 86_400 + 5 * 86_400;
+        assert_eq!(d, Duration::from_secs(expected_secs));
+    }
+
+    #[test]
+    fn test_parse_duration_small_unit() {
+        let d = parse_duration("500ms").unwrap();
+        assert_eq!(d, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_unknown_unit() {
+        let result = parse_duration("5fortnights");
+        assert!(matches!(result, Err(DateFormatError::InvalidDuration(_))));
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_malformed_token() {
+        let result = parse_duration("h30");
+        assert!(matches!(result, Err(DateFormatError::InvalidDuration(_))));
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_overflow() {
+        let result = parse_duration("99999999999999999999year");
+        assert!(matches!(
+            result,
+            Err(DateFormatError::InvalidDuration(_)) | Err(DateFormatError::IntConversion)
+        ));
+    }
+
+    #[test]
+    fn test_format_duration_largest_unit_first() {
+        let d = Duration::from_secs(90 * 60);
+        assert_eq!(format_duration(d), "1h 30m");
+    }
+
+    #[test]
+    fn test_format_duration_zero() {
+        assert_eq!(format_duration(Duration::from_secs(0)), "0s");
+    }
+
+    #[test]
+    fn test_format_duration_skips_zero_components() {
+        let d = Duration::from_millis(500);
+        assert_eq!(format_duration(d), "500ms");
+    }
+
+    #[test]
+    fn test_parse_then_format_round_trip() {
+        let parsed = parse_duration("1h 30min").unwrap();
+        assert_eq!(format_duration(parsed), "1h 30m");
+    }
 }
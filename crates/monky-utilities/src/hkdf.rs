@@ -0,0 +1,155 @@
+/*
+ * Copyright (C) 2025 Movibase Platform Private Limited
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! HKDF (RFC 5869) key derivation, instantiated with HMAC-SHA256.
+//!
+//! This lets callers derive per-topic, per-domain signing subkeys from one
+//! master secret, so a leaked or rotated topic key doesn't expose every
+//! other topic's signatures.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::kafka::core::schema::topic::Topic;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The output size, in bytes, of the underlying HMAC-SHA256 hash.
+const HASH_LEN: usize = 32;
+
+/// HKDF-Extract: `HMAC-SHA256(salt, ikm)`.
+///
+/// Per RFC 5869, an empty `salt` is treated as a string of `HASH_LEN` zero
+/// bytes.
+pub fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> [u8; HASH_LEN] {
+    let zero_salt = [0u8; HASH_LEN];
+    let salt = if salt.is_empty() { &zero_salt[..] } else { salt };
+
+    // HMAC accepts any key length, so this never fails in practice.
+    let mut mac = HmacSha256::new_from_slice(salt).expect("HMAC accepts any key length");
+    mac.update(ikm);
+    let mut prk = [0u8; HASH_LEN];
+    prk.copy_from_slice(&mac.finalize().into_bytes());
+    prk
+}
+
+/// HKDF-Expand: iterates `T(n) = HMAC-SHA256(prk, T(n-1) || info || n)`,
+/// concatenating `T(1), T(2), ...` until `len` bytes are produced.
+///
+/// `len` is capped at `255 * HASH_LEN` bytes, the RFC 5869 limit for a
+/// single-octet counter.
+pub fn hkdf_expand(prk: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+    let len = len.min(255 * HASH_LEN);
+    let blocks_needed = len.div_ceil(HASH_LEN);
+
+    let mut okm = Vec::with_capacity(blocks_needed * HASH_LEN);
+    let mut previous_block: Vec<u8> = Vec::new();
+
+    for counter in 1..=blocks_needed {
+        let mut mac = HmacSha256::new_from_slice(prk).expect("HMAC accepts any key length");
+        mac.update(&previous_block);
+        mac.update(info);
+        mac.update(&[counter as u8]);
+        previous_block = mac.finalize().into_bytes().to_vec();
+        okm.extend_from_slice(&previous_block);
+    }
+
+    okm.truncate(len);
+    okm
+}
+
+/// Derive a 32-byte signing subkey for `topic` from one `master` secret.
+///
+/// Uses `topic.kind()`/`topic.domain()` as the HKDF `info` context, so each
+/// Kafka domain (e.g. `ops.application`, `source.twilio`) signs with a
+/// cryptographically separated key.
+pub fn derive_topic_key(master: &str, topic: &dyn Topic) -> Vec<u8> {
+    let prk = hkdf_extract(&[], master.as_bytes());
+    let info = format!("{}.{}", topic.kind(), topic.domain());
+    hkdf_expand(&prk, info.as_bytes(), HASH_LEN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 5869 Appendix A, Test Case 1 (HKDF-SHA256).
+    #[test]
+    fn rfc5869_test_case_1() {
+        let ikm = [0x0bu8; 22];
+        let salt: Vec<u8> = (0x00..=0x0c).collect();
+        let info: Vec<u8> = (0xf0..=0xf9).collect();
+
+        let prk = hkdf_extract(&salt, &ikm);
+        assert_eq!(
+            hex::encode(prk),
+            "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"
+        );
+
+        let okm = hkdf_expand(&prk, &info, 42);
+        assert_eq!(
+            hex::encode(&okm),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+    }
+
+    #[test]
+    fn hkdf_expand_caps_at_255_blocks() {
+        let prk = [0x42u8; HASH_LEN];
+        let okm = hkdf_expand(&prk, b"info", 255 * HASH_LEN + 100);
+        assert_eq!(okm.len(), 255 * HASH_LEN);
+    }
+
+    struct StubTopic {
+        kind: &'static str,
+        domain: &'static str,
+    }
+
+    impl Topic for StubTopic {
+        fn kind(&self) -> &str {
+            self.kind
+        }
+
+        fn domain(&self) -> &str {
+            self.domain
+        }
+
+        fn dataset(&self) -> &str {
+            "dataset"
+        }
+    }
+
+    #[test]
+    fn derive_topic_key_is_deterministic_and_domain_separated() {
+        let ops = StubTopic {
+            kind: "ops",
+            domain: "application",
+        };
+        let source = StubTopic {
+            kind: "source",
+            domain: "twilio",
+        };
+
+        let key_a = derive_topic_key("root-secret", &ops);
+        let key_b = derive_topic_key("root-secret", &ops);
+        assert_eq!(key_a, key_b);
+
+        let key_other = derive_topic_key("root-secret", &source);
+        assert_ne!(key_a, key_other);
+        assert_eq!(key_a.len(), HASH_LEN);
+    }
+}
@@ -15,22 +15,48 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use serde::Deserialize;
 use serde_json::Value;
 use std::error::Error;
 use std::fmt;
 use std::str;
+use std::sync::Arc;
 
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+
+use crate::kafka::core::schema::topic::Topic;
+use crate::kafka::core::serdes::capability::{
+    self, CapabilityError, DidKeyResolver, CAPABILITY_BODY_KEY, CAPABILITY_TOKEN_KEY,
+};
 use crate::kafka::core::serdes::hybrid_object_mapper::HybridObjectMapper;
+use crate::kafka::core::serdes::signing;
+use crate::kafka::core::serdes::wire_format::{self, WireFormatError};
 
 /// Error type analogous to Kafka's `SerializationException` (no external crates).
 #[derive(Debug)]
 pub enum SerializationError {
-    /// The incoming payload is too short to skip the header byte.
+    /// The incoming payload is too short to skip the header bytes.
     PayloadTooShort,
     /// Payload is not valid UTF-8.
     InvalidUtf8(std::str::Utf8Error),
     /// JSON (de)serialization error from serde_json.
     Json(serde_json::Error),
+    /// The envelope did not have the expected `header.payload[.signature]`
+    /// shape, or its signature/authentication tag did not verify.
+    SignatureInvalid,
+    /// A field encoded via `serdes::base64` (or `ByteBuf`) was not valid
+    /// base64.
+    InvalidBase64(base64::DecodeError),
+    /// The payload's schema-version byte exceeds `max_schema_version`.
+    UnsupportedSchemaVersion { found: u8, max: u8 },
+    /// The payload's format-id byte doesn't match a known `WireFormat`.
+    UnsupportedWireFormat(u8),
+    /// Decoding the payload through the resolved `WireFormat` failed.
+    WireFormat(WireFormatError),
+    /// The target `Topic` is capability-gated but the payload carried no
+    /// capability token, its chain failed to verify, or its final
+    /// capability doesn't cover this topic/action.
+    Unauthorized(CapabilityError),
 }
 
 
@@ -38,10 +64,24 @@ impl fmt::Display for SerializationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             SerializationError::PayloadTooShort => {
-                write!(f, "payload too short (need at least 1 byte to skip header)")
+                write!(f, "payload too short (need at least magic byte + schema version + format id)")
             }
             SerializationError::InvalidUtf8(e) => write!(f, "invalid utf-8 payload: {}", e),
             SerializationError::Json(e) => write!(f, "json deserialization error: {}", e),
+            SerializationError::SignatureInvalid => {
+                write!(f, "envelope signature or authentication tag is invalid")
+            }
+            SerializationError::InvalidBase64(e) => write!(f, "invalid base64 field: {}", e),
+            SerializationError::UnsupportedSchemaVersion { found, max } => write!(
+                f,
+                "unsupported schema version {} (max supported is {})",
+                found, max
+            ),
+            SerializationError::UnsupportedWireFormat(id) => {
+                write!(f, "unsupported wire-format id {}", id)
+            }
+            SerializationError::WireFormat(e) => write!(f, "wire-format error: {}", e),
+            SerializationError::Unauthorized(e) => write!(f, "unauthorized: {}", e),
         }
     }
 }
@@ -53,16 +93,40 @@ impl Error for SerializationError {
             SerializationError::PayloadTooShort => None,
             SerializationError::InvalidUtf8(e) => Some(e),
             SerializationError::Json(e) => Some(e),
+            SerializationError::SignatureInvalid => None,
+            SerializationError::InvalidBase64(e) => Some(e),
+            SerializationError::UnsupportedSchemaVersion { .. } => None,
+            SerializationError::UnsupportedWireFormat(_) => None,
+            SerializationError::WireFormat(e) => Some(e),
+            SerializationError::Unauthorized(e) => Some(e),
         }
     }
 }
 
+impl From<WireFormatError> for SerializationError {
+    fn from(e: WireFormatError) -> Self {
+        SerializationError::WireFormat(e)
+    }
+}
+
+impl From<CapabilityError> for SerializationError {
+    fn from(e: CapabilityError) -> Self {
+        SerializationError::Unauthorized(e)
+    }
+}
+
 impl From<serde_json::Error> for SerializationError {
     fn from(e: serde_json::Error) -> Self {
         SerializationError::Json(e)
     }
 }
 
+impl From<base64::DecodeError> for SerializationError {
+    fn from(e: base64::DecodeError) -> Self {
+        SerializationError::InvalidBase64(e)
+    }
+}
+
 
 impl From<std::str::Utf8Error> for SerializationError {
     fn from(e: std::str::Utf8Error) -> Self {
@@ -71,10 +135,19 @@ impl From<std::str::Utf8Error> for SerializationError {
 }
 
 /// Stateless deserializer. It uses a `HybridObjectMapper` instance to parse the JSON body after skipping
-/// the first byte of the payload.
+/// the magic byte, schema-version byte and format-id byte of the payload.
 #[derive(Debug, Default)]
 pub struct KafkaDeserializer {
     mapper: HybridObjectMapper,
+    /// Highest schema-version byte this deserializer will accept. Payloads
+    /// stamped with a higher version are rejected with
+    /// `SerializationError::UnsupportedSchemaVersion` rather than being
+    /// parsed as if they matched the known shape. Defaults to
+    /// [`CURRENT_SCHEMA_VERSION`](crate::kafka::core::serializer::kafka_serializer::CURRENT_SCHEMA_VERSION).
+    pub max_schema_version: u8,
+    /// Resolves a `CapabilityLink`'s `issuer_did` to its public key. Required
+    /// whenever the target `Topic::required_capability()` returns `Some`.
+    pub did_resolver: Option<Arc<dyn DidKeyResolver>>,
 }
 
 impl KafkaDeserializer {
@@ -82,39 +155,213 @@ impl KafkaDeserializer {
     pub fn new() -> Self {
         KafkaDeserializer {
             mapper: HybridObjectMapper::new(),
+            max_schema_version: crate::kafka::core::serializer::kafka_serializer::CURRENT_SCHEMA_VERSION,
+            did_resolver: None,
         }
     }
 
     /// Construct with a preconfigured HybridObjectMapper.
     pub fn with_mapper(mapper: HybridObjectMapper) -> Self {
-        KafkaDeserializer { mapper }
+        KafkaDeserializer {
+            mapper,
+            max_schema_version: crate::kafka::core::serializer::kafka_serializer::CURRENT_SCHEMA_VERSION,
+            did_resolver: None,
+        }
     }
 
     /// Deserialize the given Kafka payload bytes into `serde_json::Value`.
     ///
-    /// `topic` parameter kept for API parity but unused.
+    /// Thin wrapper over [`deserialize_versioned`](Self::deserialize_versioned)
+    /// for callers that don't need the schema version.
+    ///
+    /// Returns `SerializationError` on any problem (short payload, unsupported
+    /// schema version, unknown wire-format id, invalid utf-8, or JSON parse
+    /// issues).
+    pub fn deserialize(&self, topic: &dyn Topic, bytes: &[u8]) -> Result<Value, SerializationError> {
+        self.deserialize_versioned(topic, bytes).map(|(_, value)| value)
+    }
+
+    /// Deserialize the given Kafka payload bytes into `serde_json::Value`,
+    /// also returning the schema-version byte the payload was stamped with
+    /// so callers can migrate/route on it.
     ///
-    /// Returns `SerializationError` on any problem (short payload, invalid utf-8,
-    /// or JSON parse issues).
-    pub fn deserialize(&self, _topic: &str, bytes: &[u8]) -> Result<Value, SerializationError> {
-        if bytes.len() < 1 {
+    /// Returns `SerializationError` on any problem (short payload, unsupported
+    /// schema version, unknown wire-format id, invalid utf-8, or JSON parse
+    /// issues).
+    pub fn deserialize_versioned(
+        &self,
+        topic: &dyn Topic,
+        bytes: &[u8],
+    ) -> Result<(u8, Value), SerializationError> {
+        if bytes.len() < 3 {
             return Err(SerializationError::PayloadTooShort);
         }
 
-        // Skip first byte without copying
-        let payload = &bytes[1..];
+        let found_version = bytes[1];
+        if found_version > self.max_schema_version {
+            return Err(SerializationError::UnsupportedSchemaVersion {
+                found: found_version,
+                max: self.max_schema_version,
+            });
+        }
+
+        let format_id = bytes[2];
+
+        // Skip magic byte, schema-version byte, and format-id byte without copying
+        let payload = &bytes[3..];
+
+        // Signing/encryption envelopes are always carried as JSON text
+        // (`KafkaSerializer` always stamps format id 0 for them), so those
+        // branches read `payload` as `&str` regardless of `format_id`.
+        if let Some(encryption) = &self.mapper.encryption {
+            let s = str::from_utf8(payload)?;
+            let value = self.decrypt_envelope(s, encryption)?;
+            let value = self.validate_capability_token(value, topic)?;
+            return Ok((found_version, value));
+        }
+
+        if let Some(signing_config) = &self.mapper.signing {
+            let s = str::from_utf8(payload)?;
+            let payload_json = signing::decode_jws(s, signing_config)
+                .ok_or(SerializationError::SignatureInvalid)?;
+            let value = serde_json::from_slice(&payload_json).map_err(SerializationError::Json)?;
+            let value = self.validate_capability_token(value, topic)?;
+            return Ok((found_version, value));
+        }
+
+        let format = wire_format::format_for_id(format_id)
+            .ok_or(SerializationError::UnsupportedWireFormat(format_id))?;
+        let value = format.decode(payload)?;
+        let value = self.validate_capability_token(value, topic)?;
+
+        // Unwrap an adjacent `{"@type": ..., "value": ...}` tag if present.
+        let value = self
+            .mapper
+            .unwrap_type_tagged::<Value>(value)
+            .map_err(SerializationError::Json)?;
+        Ok((found_version, value))
+    }
+
+    /// Strip and, if `topic.required_capability()` is `Some`, validate a
+    /// `{"@capability_token": ..., "@body": ...}` envelope produced by
+    /// `KafkaSerializer::attach_capability_token`, returning the inner body.
+    ///
+    /// If the topic isn't capability-gated, a present token is stripped but
+    /// not checked. If it is, a missing token, an unverifiable chain, or a
+    /// final capability that doesn't cover `topic.name()`/the required
+    /// action all surface as `SerializationError::Unauthorized`.
+    fn validate_capability_token(
+        &self,
+        value: Value,
+        topic: &dyn Topic,
+    ) -> Result<Value, SerializationError> {
+        let required_action = topic.required_capability().map(|c| c.action);
+
+        let Value::Object(mut map) = value else {
+            return match required_action {
+                Some(_) => Err(SerializationError::Unauthorized(CapabilityError::MissingToken)),
+                None => Ok(value),
+            };
+        };
+
+        let Some(token_value) = map.remove(CAPABILITY_TOKEN_KEY) else {
+            return match required_action {
+                Some(_) => Err(SerializationError::Unauthorized(CapabilityError::MissingToken)),
+                None => Ok(Value::Object(map)),
+            };
+        };
+
+        let body = map
+            .remove(CAPABILITY_BODY_KEY)
+            .ok_or(SerializationError::Unauthorized(CapabilityError::MissingToken))?;
 
-        // Convert to &str (serde_json::from_str/HybridObjectMapper methods operate on str)
-        let s = str::from_utf8(payload)?;
+        let Some(required_action) = required_action else {
+            // Topic isn't capability-gated: strip the token, pass the body through.
+            return Ok(body);
+        };
 
-        // Try to use mapper.deserialize_with_type to support type-wrapped payloads
-        // Fallback to plain deserialize if needed.
-        //
-        // hybrid mapper's deserialize_with_type returns Result<T, serde_json::Error>.
-        match self.mapper.deserialize_with_type::<Value>(s) {
-            Ok(v) => Ok(v),
-            Err(e) => Err(SerializationError::Json(e)),
+        let resolver = self
+            .did_resolver
+            .as_deref()
+            .ok_or(SerializationError::Unauthorized(CapabilityError::MissingResolver))?;
+        let token: capability::CapabilityToken =
+            serde_json::from_value(token_value).map_err(SerializationError::Json)?;
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        token.authorizes(resolver, now, &topic.name(), required_action)?;
+
+        Ok(body)
+    }
+
+    /// Deserialize the given Kafka payload bytes into `T`, borrowing `str`/
+    /// `[u8]` fields from `bytes` instead of allocating where the shape of
+    /// `T` (e.g. fields typed `&'de str` or [`CowStr`](crate::kafka::core::serdes::borrowed::CowStr))
+    /// allows it.
+    ///
+    /// # Lifetime invariant
+    ///
+    /// The returned `T` may borrow from `bytes`; it must not outlive `bytes`,
+    /// matching the invariant documented on
+    /// [`crate::kafka::core::serdes::borrowed`]. This bypasses
+    /// `HybridObjectMapper`'s signing/encryption/type-tagging envelope modes
+    /// and always assumes the payload's format id is
+    /// [`JsonFormat`](crate::kafka::core::serdes::wire_format::JsonFormat) —
+    /// use it only for plain, unwrapped JSON payloads.
+    pub fn deserialize_borrowed<'de, T: Deserialize<'de>>(
+        &self,
+        _topic: &str,
+        bytes: &'de [u8],
+    ) -> Result<T, SerializationError> {
+        if bytes.len() < 3 {
+            return Err(SerializationError::PayloadTooShort);
+        }
+
+        let found_version = bytes[1];
+        if found_version > self.max_schema_version {
+            return Err(SerializationError::UnsupportedSchemaVersion {
+                found: found_version,
+                max: self.max_schema_version,
+            });
         }
+
+        let payload = &bytes[3..];
+        serde_json::from_slice(payload).map_err(SerializationError::Json)
+    }
+
+    /// Decode a base64 field value (as produced by `serdes::base64::serialize`
+    /// or `serdes::base64::ByteBuf`) into raw bytes, surfacing malformed
+    /// base64 as a typed `SerializationError` instead of a generic JSON
+    /// error.
+    pub fn decode_base64_field(&self, encoded: &str) -> Result<Vec<u8>, SerializationError> {
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(SerializationError::from)
+    }
+
+    /// Decode a `header_b64.ciphertext_b64` JWE-style envelope produced by
+    /// `KafkaSerializer`'s encryption mode.
+    fn decrypt_envelope(
+        &self,
+        body: &str,
+        encryption: &signing::EncryptionConfig,
+    ) -> Result<Value, SerializationError> {
+        let mut parts = body.splitn(2, '.');
+        let header_b64 = parts.next().ok_or(SerializationError::SignatureInvalid)?;
+        let ciphertext_b64 = parts.next().ok_or(SerializationError::SignatureInvalid)?;
+
+        let header_bytes = URL_SAFE_NO_PAD
+            .decode(header_b64)
+            .map_err(|_| SerializationError::SignatureInvalid)?;
+        let header: Value = serde_json::from_slice(&header_bytes)?;
+        let nonce_b64 = header["nonce"]
+            .as_str()
+            .ok_or(SerializationError::SignatureInvalid)?;
+        let tag_b64 = header["tag"]
+            .as_str()
+            .ok_or(SerializationError::SignatureInvalid)?;
+
+        let plaintext = signing::decrypt_jwe(ciphertext_b64, nonce_b64, tag_b64, encryption)
+            .ok_or(SerializationError::SignatureInvalid)?;
+        serde_json::from_slice(&plaintext).map_err(SerializationError::Json)
     }
 }
 
@@ -123,11 +370,50 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    /// Minimal test-only `Topic` impl. `required_capability` defaults to
+    /// `None`; capability-gating tests set it explicitly.
+    struct StubTopic {
+        kind: &'static str,
+        domain: &'static str,
+        dataset: &'static str,
+        required_capability: Option<capability::Capability>,
+    }
+
+    impl StubTopic {
+        fn new(kind: &'static str, domain: &'static str, dataset: &'static str) -> Self {
+            StubTopic {
+                kind,
+                domain,
+                dataset,
+                required_capability: None,
+            }
+        }
+    }
+
+    impl Topic for StubTopic {
+        fn kind(&self) -> &str {
+            self.kind
+        }
+
+        fn domain(&self) -> &str {
+            self.domain
+        }
+
+        fn dataset(&self) -> &str {
+            self.dataset
+        }
+
+        fn required_capability(&self) -> Option<capability::Capability> {
+            self.required_capability.clone()
+        }
+    }
+
     #[test]
     fn deserialize_valid_payload_without_type_wrapper() {
         let des = KafkaDeserializer::new();
-        let payload = b"\x00{\"k\":\"v\"}";
-        let v = des.deserialize("topic", payload).expect("should parse");
+        let payload = b"\x00\x00\x00{\"k\":\"v\"}";
+        let topic = StubTopic::new("ops", "application", "topic");
+        let v = des.deserialize(&topic, payload).expect("should parse");
         assert_eq!(v, json!({"k":"v"}));
     }
 
@@ -139,43 +425,364 @@ mod tests {
         let des = KafkaDeserializer::with_mapper(mapper);
 
         // Prepare adjacent-wrapped JSON: {"@type":"com.example","value":{"k":"v"}}
-        let _wrapped = br#"\x00{\"@type\":\"com.example\",\"value\":{\"k\":\"v\"}}"#;
-        // Note: the raw bytes above include \x00 as two characters; construct properly:
-        let mut buf = vec![0u8];
+        let mut buf = vec![0u8, 0u8, 0u8];
         buf.extend_from_slice(b"{\"@type\":\"com.example\",\"value\":{\"k\":\"v\"}}");
 
-        let v = des.deserialize("topic", &buf).expect("should parse wrapped");
+        let topic = StubTopic::new("ops", "application", "topic");
+        let v = des.deserialize(&topic, &buf).expect("should parse wrapped");
         assert_eq!(v, json!({"k":"v"}));
     }
 
     #[test]
     fn deserialize_empty_payload() {
         let des = KafkaDeserializer::new();
-        let err = des.deserialize("topic", &[]).unwrap_err();
+        let topic = StubTopic::new("ops", "application", "topic");
+        let err = des.deserialize(&topic, &[]).unwrap_err();
         matches!(err, SerializationError::PayloadTooShort);
     }
 
     #[test]
     fn deserialize_invalid_utf8() {
         let des = KafkaDeserializer::new();
-        // invalid UTF-8 after skipping first byte
-        let payload = &[0u8, 0xff, 0xff, 0xff];
-        let err = des.deserialize("t", payload).unwrap_err();
+        // JSON format id, then invalid UTF-8 body
+        let payload = &[0u8, 0u8, 0u8, 0xff, 0xff];
+        let topic = StubTopic::new("ops", "application", "t");
+        let err = des.deserialize(&topic, payload).unwrap_err();
         match err {
-            SerializationError::InvalidUtf8(_) => {}
-            _ => panic!("expected InvalidUtf8"),
+            SerializationError::WireFormat(_) => {}
+            _ => panic!("expected WireFormat error"),
         }
     }
 
     #[test]
     fn deserialize_invalid_json() {
         let des = KafkaDeserializer::new();
-        let mut buf = vec![0u8];
+        let mut buf = vec![0u8, 0u8, 0u8];
         buf.extend_from_slice(b"{not:json}");
-        let err = des.deserialize("t", &buf).unwrap_err();
+        let topic = StubTopic::new("ops", "application", "t");
+        let err = des.deserialize(&topic, &buf).unwrap_err();
         match err {
-            SerializationError::Json(_) => {}
-            _ => panic!("expected Json error"),
+            SerializationError::WireFormat(_) => {}
+            _ => panic!("expected WireFormat error"),
         }
     }
+
+    #[test]
+    fn deserialize_rejects_unknown_wire_format_id() {
+        let des = KafkaDeserializer::new();
+        let mut buf = vec![0u8, 0u8, 99u8];
+        buf.extend_from_slice(b"{\"k\":\"v\"}");
+        let topic = StubTopic::new("ops", "application", "t");
+        let err = des.deserialize(&topic, &buf).unwrap_err();
+        assert!(matches!(err, SerializationError::UnsupportedWireFormat(99)));
+    }
+
+    #[test]
+    fn url_encoded_round_trips_through_serializer_and_deserializer() {
+        use crate::kafka::core::serdes::wire_format::WireFormatKind;
+        use crate::kafka::core::serializer::kafka_serializer::KafkaSerializer;
+
+        let mut mapper = HybridObjectMapper::new();
+        mapper.wire_format = WireFormatKind::UrlEncoded;
+        let ser = KafkaSerializer::with_mapper(mapper.clone());
+        let des = KafkaDeserializer::with_mapper(mapper);
+
+        let topic = StubTopic::new("ops", "application", "topic");
+        let bytes = ser.serialize(&topic.name(), &json!({"name": "alice"})).unwrap();
+        let decoded = des.deserialize(&topic, &bytes).unwrap();
+        assert_eq!(decoded["name"], "alice");
+    }
+
+    #[test]
+    fn cbor_round_trips_through_serializer_and_deserializer() {
+        use crate::kafka::core::serdes::wire_format::WireFormatKind;
+        use crate::kafka::core::serializer::kafka_serializer::KafkaSerializer;
+
+        let mut mapper = HybridObjectMapper::new();
+        mapper.wire_format = WireFormatKind::Cbor;
+        let ser = KafkaSerializer::with_mapper(mapper.clone());
+        let des = KafkaDeserializer::with_mapper(mapper);
+
+        let topic = StubTopic::new("ops", "application", "topic");
+        let bytes = ser
+            .serialize(&topic.name(), &json!({"k": "v", "n": 3}))
+            .unwrap();
+        let decoded = des.deserialize(&topic, &bytes).unwrap();
+        assert_eq!(decoded, json!({"k": "v", "n": 3}));
+    }
+
+    #[test]
+    fn hs256_signed_envelope_round_trips() {
+        use crate::kafka::core::serializer::kafka_serializer::KafkaSerializer;
+
+        let mut ser_mapper = HybridObjectMapper::new();
+        ser_mapper.signing = Some(signing::SigningConfig::Hs256 {
+            key: b"secret".to_vec(),
+        });
+        let ser = KafkaSerializer::with_mapper(ser_mapper);
+
+        let mut des_mapper = HybridObjectMapper::new();
+        des_mapper.signing = Some(signing::SigningConfig::Hs256 {
+            key: b"secret".to_vec(),
+        });
+        let des = KafkaDeserializer::with_mapper(des_mapper);
+
+        let topic = StubTopic::new("ops", "application", "topic");
+        let bytes = ser.serialize(&topic.name(), &json!({"k": "v"})).unwrap();
+        let decoded = des.deserialize(&topic, &bytes).unwrap();
+        assert_eq!(decoded, json!({"k": "v"}));
+    }
+
+    #[test]
+    fn signed_envelope_rejects_wrong_key() {
+        use crate::kafka::core::serializer::kafka_serializer::KafkaSerializer;
+
+        let mut ser_mapper = HybridObjectMapper::new();
+        ser_mapper.signing = Some(signing::SigningConfig::Hs256 {
+            key: b"secret".to_vec(),
+        });
+        let ser = KafkaSerializer::with_mapper(ser_mapper);
+
+        let mut des_mapper = HybridObjectMapper::new();
+        des_mapper.signing = Some(signing::SigningConfig::Hs256 {
+            key: b"wrong-secret".to_vec(),
+        });
+        let des = KafkaDeserializer::with_mapper(des_mapper);
+
+        let topic = StubTopic::new("ops", "application", "topic");
+        let bytes = ser.serialize(&topic.name(), &json!({"k": "v"})).unwrap();
+        let err = des.deserialize(&topic, &bytes).unwrap_err();
+        assert!(matches!(err, SerializationError::SignatureInvalid));
+    }
+
+    #[test]
+    fn encrypted_envelope_round_trips() {
+        use crate::kafka::core::serializer::kafka_serializer::KafkaSerializer;
+
+        let encryption = signing::EncryptionConfig { key: [3u8; 32] };
+
+        let mut ser_mapper = HybridObjectMapper::new();
+        ser_mapper.encryption = Some(encryption.clone());
+        let ser = KafkaSerializer::with_mapper(ser_mapper);
+
+        let mut des_mapper = HybridObjectMapper::new();
+        des_mapper.encryption = Some(encryption);
+        let des = KafkaDeserializer::with_mapper(des_mapper);
+
+        let topic = StubTopic::new("ops", "application", "topic");
+        let bytes = ser.serialize(&topic.name(), &json!({"k": "v"})).unwrap();
+        let decoded = des.deserialize(&topic, &bytes).unwrap();
+        assert_eq!(decoded, json!({"k": "v"}));
+    }
+
+    #[test]
+    fn decode_base64_field_round_trips() {
+        use crate::kafka::core::serdes::base64::ByteBuf;
+
+        let des = KafkaDeserializer::new();
+        let buf = ByteBuf(vec![1, 2, 3, 4]);
+        let encoded = serde_json::to_string(&buf).unwrap();
+        let encoded = encoded.trim_matches('"');
+
+        let decoded = des.decode_base64_field(encoded).unwrap();
+        assert_eq!(decoded, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn decode_base64_field_rejects_malformed_input() {
+        let des = KafkaDeserializer::new();
+        let err = des.decode_base64_field("not-valid-base64!").unwrap_err();
+        assert!(matches!(err, SerializationError::InvalidBase64(_)));
+    }
+
+    #[test]
+    fn deserialize_borrowed_borrows_str_fields() {
+        use crate::kafka::core::serdes::borrowed::CowStr;
+        use serde::Deserialize;
+
+        #[derive(Deserialize)]
+        struct Msg<'de> {
+            #[serde(borrow)]
+            name: CowStr<'de>,
+        }
+
+        let des = KafkaDeserializer::new();
+        let mut buf = vec![0u8, 0u8, 0u8];
+        buf.extend_from_slice(b"{\"name\":\"alice\"}");
+
+        let msg: Msg = des.deserialize_borrowed("topic", &buf).unwrap();
+        assert_eq!(msg.name.0, "alice");
+        assert!(matches!(msg.name.0, std::borrow::Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn deserialize_borrowed_rejects_short_payload() {
+        let des = KafkaDeserializer::new();
+        let err = des.deserialize_borrowed::<Value>("topic", &[]).unwrap_err();
+        assert!(matches!(err, SerializationError::PayloadTooShort));
+    }
+
+    #[test]
+    fn deserialize_rejects_schema_version_above_ceiling() {
+        use crate::kafka::core::serializer::kafka_serializer::KafkaSerializer;
+
+        let mut ser = KafkaSerializer::new();
+        ser.schema_version = 2;
+        let des = KafkaDeserializer::new();
+        let topic = StubTopic::new("ops", "application", "topic");
+
+        let bytes = ser.serialize(&topic.name(), &json!({"k": "v"})).unwrap();
+        let err = des.deserialize(&topic, &bytes).unwrap_err();
+        assert!(matches!(
+            err,
+            SerializationError::UnsupportedSchemaVersion { found: 2, max: 0 }
+        ));
+    }
+
+    #[test]
+    fn deserialize_accepts_schema_version_within_ceiling() {
+        use crate::kafka::core::serializer::kafka_serializer::KafkaSerializer;
+
+        let mut ser = KafkaSerializer::new();
+        ser.schema_version = 2;
+        let mut des = KafkaDeserializer::new();
+        des.max_schema_version = 2;
+        let topic = StubTopic::new("ops", "application", "topic");
+
+        let bytes = ser.serialize(&topic.name(), &json!({"k": "v"})).unwrap();
+        let decoded = des.deserialize(&topic, &bytes).unwrap();
+        assert_eq!(decoded, json!({"k": "v"}));
+    }
+
+    #[test]
+    fn deserialize_versioned_exposes_schema_version() {
+        use crate::kafka::core::serializer::kafka_serializer::KafkaSerializer;
+
+        let mut ser = KafkaSerializer::new();
+        ser.schema_version = 2;
+        let mut des = KafkaDeserializer::new();
+        des.max_schema_version = 2;
+        let topic = StubTopic::new("ops", "application", "topic");
+
+        let bytes = ser.serialize(&topic.name(), &json!({"k": "v"})).unwrap();
+        let (version, decoded) = des.deserialize_versioned(&topic, &bytes).unwrap();
+        assert_eq!(version, 2);
+        assert_eq!(decoded, json!({"k": "v"}));
+    }
+
+    #[derive(Debug)]
+    struct MapResolver(std::collections::HashMap<String, p256::ecdsa::VerifyingKey>);
+
+    impl DidKeyResolver for MapResolver {
+        fn resolve(&self, did: &str) -> Option<p256::ecdsa::VerifyingKey> {
+            self.0.get(did).cloned()
+        }
+    }
+
+    #[test]
+    fn capability_gated_topic_accepts_valid_token() {
+        use crate::kafka::core::serdes::capability::{Action, Capability, CapabilityLink, CapabilityToken};
+        use crate::kafka::core::serializer::kafka_serializer::KafkaSerializer;
+        use p256::ecdsa::SigningKey;
+
+        let issuer_key = SigningKey::random(&mut rand::thread_rng());
+        let issuer_vk = p256::ecdsa::VerifyingKey::from(&issuer_key);
+        let mut resolver_map = std::collections::HashMap::new();
+        resolver_map.insert("did:monky:root".to_string(), issuer_vk);
+
+        let link = CapabilityLink::sign(
+            "did:monky:root".to_string(),
+            "did:monky:producer-a".to_string(),
+            Capability {
+                topic_pattern: "application.communication.*".to_string(),
+                action: Action::Produce,
+            },
+            0,
+            4_000_000_000,
+            &issuer_key,
+        )
+        .unwrap();
+
+        let mut ser = KafkaSerializer::new();
+        ser.capability_token = Some(CapabilityToken(vec![link]));
+
+        let mut des = KafkaDeserializer::new();
+        des.did_resolver = Some(std::sync::Arc::new(MapResolver(resolver_map)));
+
+        let mut topic = StubTopic::new("application", "communication", "messages");
+        topic.required_capability = Some(Capability {
+            topic_pattern: "application.communication.*".to_string(),
+            action: Action::Produce,
+        });
+
+        let bytes = ser.serialize(&topic.name(), &json!({"k": "v"})).unwrap();
+        let decoded = des.deserialize(&topic, &bytes).unwrap();
+        assert_eq!(decoded, json!({"k": "v"}));
+    }
+
+    #[test]
+    fn capability_gated_topic_rejects_missing_token() {
+        use crate::kafka::core::serdes::capability::{Action, Capability};
+
+        let mut des = KafkaDeserializer::new();
+        des.did_resolver = Some(std::sync::Arc::new(MapResolver(std::collections::HashMap::new())));
+
+        let mut topic = StubTopic::new("application", "communication", "messages");
+        topic.required_capability = Some(Capability {
+            topic_pattern: "application.communication.*".to_string(),
+            action: Action::Produce,
+        });
+
+        let mut buf = vec![0u8, 0u8, 0u8];
+        buf.extend_from_slice(b"{\"k\":\"v\"}");
+        let err = des.deserialize(&topic, &buf).unwrap_err();
+        assert!(matches!(
+            err,
+            SerializationError::Unauthorized(CapabilityError::MissingToken)
+        ));
+    }
+
+    #[test]
+    fn capability_gated_topic_rejects_token_for_other_topic() {
+        use crate::kafka::core::serdes::capability::{Action, Capability, CapabilityLink, CapabilityToken};
+        use crate::kafka::core::serializer::kafka_serializer::KafkaSerializer;
+        use p256::ecdsa::SigningKey;
+
+        let issuer_key = SigningKey::random(&mut rand::thread_rng());
+        let issuer_vk = p256::ecdsa::VerifyingKey::from(&issuer_key);
+        let mut resolver_map = std::collections::HashMap::new();
+        resolver_map.insert("did:monky:root".to_string(), issuer_vk);
+
+        let link = CapabilityLink::sign(
+            "did:monky:root".to_string(),
+            "did:monky:producer-a".to_string(),
+            Capability {
+                topic_pattern: "ops.application.*".to_string(),
+                action: Action::Produce,
+            },
+            0,
+            4_000_000_000,
+            &issuer_key,
+        )
+        .unwrap();
+
+        let mut ser = KafkaSerializer::new();
+        ser.capability_token = Some(CapabilityToken(vec![link]));
+
+        let mut des = KafkaDeserializer::new();
+        des.did_resolver = Some(std::sync::Arc::new(MapResolver(resolver_map)));
+
+        let mut topic = StubTopic::new("application", "communication", "messages");
+        topic.required_capability = Some(Capability {
+            topic_pattern: "application.communication.*".to_string(),
+            action: Action::Produce,
+        });
+
+        let bytes = ser.serialize(&topic.name(), &json!({"k": "v"})).unwrap();
+        let err = des.deserialize(&topic, &bytes).unwrap_err();
+        assert!(matches!(
+            err,
+            SerializationError::Unauthorized(CapabilityError::Unauthorized)
+        ));
+    }
 }
\ No newline at end of file
@@ -20,6 +20,8 @@ use std::env;
 
 use once_cell::sync::Lazy;
 
+use crate::kafka::core::serdes::capability::Capability;
+
 static MONKY_NAMESPACE: Lazy<String> = Lazy::new(|| {
     match env::var("MONKY_CORE_NAMESPACE") {
         Ok(ns) if !ns.is_empty() => format!("{}.", ns),
@@ -44,6 +46,14 @@ pub trait Topic {
         HashMap::new()
     }
 
+    /// The capability a producer/consumer must hold to use this topic, if
+    /// it's capability-gated. `None` (the default) means the topic has no
+    /// capability requirement — `KafkaSerializer`/`KafkaDeserializer` won't
+    /// attach or validate a token for it.
+    fn required_capability(&self) -> Option<Capability> {
+        None
+    }
+
     fn name(&self) -> String {
         format!(
             "{}{}.{}.{}",
@@ -0,0 +1,133 @@
+/*
+ * Copyright (C) 2025 Movibase Platform Private Limited
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Serde helper for carrying binary fields through the JSON wire format as
+//! standard base64 strings instead of JSON number arrays.
+//!
+//! Use it on a field directly:
+//!
+//! ```ignore
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Attachment {
+//!     #[serde(with = "crate::kafka::core::serdes::base64")]
+//!     payload: Vec<u8>,
+//! }
+//! ```
+//!
+//! or reach for [`ByteBuf`] when you'd rather have a standalone type.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// `#[serde(with = "...")]` serialize half: emit `bytes` as a base64 string.
+pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&STANDARD.encode(bytes))
+}
+
+/// `#[serde(with = "...")]` deserialize half: decode a base64 string into bytes.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+}
+
+/// An owned byte buffer that (de)serializes as a base64 string rather than a
+/// JSON array of numbers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ByteBuf(pub Vec<u8>);
+
+impl From<Vec<u8>> for ByteBuf {
+    fn from(bytes: Vec<u8>) -> Self {
+        ByteBuf(bytes)
+    }
+}
+
+impl From<ByteBuf> for Vec<u8> {
+    fn from(buf: ByteBuf) -> Self {
+        buf.0
+    }
+}
+
+impl Serialize for ByteBuf {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(ByteBuf)
+    }
+}
+
+/// A borrowed byte slice that serializes as a base64 string. Useful when you
+/// have a `&[u8]` and don't want to clone it into a [`ByteBuf`] just to
+/// serialize it once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bytes<'a>(pub &'a [u8]);
+
+impl<'a> Serialize for Bytes<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self.0, serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Payload {
+        #[serde(with = "self")]
+        data: Vec<u8>,
+    }
+
+    #[test]
+    fn field_round_trips_as_base64_string() {
+        let payload = Payload {
+            data: vec![0xde, 0xad, 0xbe, 0xef],
+        };
+        let json = serde_json::to_string(&payload).unwrap();
+        assert!(json.contains("\"data\":\"3q2+7w==\""));
+
+        let decoded: Payload = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.data, payload.data);
+    }
+
+    #[test]
+    fn byte_buf_round_trips() {
+        let buf = ByteBuf(vec![1, 2, 3]);
+        let json = serde_json::to_string(&buf).unwrap();
+        assert_eq!(json, "\"AQID\"");
+        let decoded: ByteBuf = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, buf);
+    }
+
+    #[test]
+    fn byte_buf_rejects_malformed_base64() {
+        let result: Result<ByteBuf, _> = serde_json::from_str("\"not-valid-base64!\"");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn bytes_serializes_borrowed_slice() {
+        let data = [1u8, 2, 3];
+        let wrapper = Bytes(&data);
+        let json = serde_json::to_string(&wrapper).unwrap();
+        assert_eq!(json, "\"AQID\"");
+    }
+}
@@ -0,0 +1,91 @@
+/*
+ * Copyright (C) 2025 Movibase Platform Private Limited
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Borrowed-data wrappers for use with
+//! [`KafkaDeserializer::deserialize_borrowed`](super::super::deserializer::kafka_deserializer::KafkaDeserializer::deserialize_borrowed).
+//!
+//! # Lifetime invariant
+//!
+//! A value built from [`CowStr`]/[`CowBytes`] may hold a `Cow::Borrowed` slice
+//! into the original input buffer. That borrow is only valid for as long as
+//! the buffer it was parsed from is alive; it must not outlive that buffer,
+//! and the buffer must not be mutated while the borrowed value is in use.
+//! Prefer `.into_owned()` on the inner `Cow` before storing the value past
+//! the scope of the source bytes.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A string that borrows from the deserializer's input when the JSON payload
+/// does not require unescaping, avoiding an allocation per field.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct CowStr<'de>(#[serde(borrow)] pub Cow<'de, str>);
+
+/// A byte buffer carried as base64 on the wire (see
+/// [`crate::kafka::core::serdes::base64`]). Base64 decoding always produces
+/// an owned buffer, so unlike [`CowStr`] this never actually borrows — the
+/// `Cow` wrapper exists so callers can write lifetime-generic code against
+/// both types uniformly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CowBytes<'de>(pub Cow<'de, [u8]>);
+
+impl<'de> Serialize for CowBytes<'de> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::kafka::core::serdes::base64::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CowBytes<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = crate::kafka::core::serdes::base64::deserialize(deserializer)?;
+        Ok(CowBytes(Cow::Owned(bytes)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cow_str_borrows_from_input_when_unescaped() {
+        let input = r#""hello""#;
+        let parsed: CowStr = serde_json::from_str(input).unwrap();
+        assert_eq!(parsed.0, "hello");
+        assert!(matches!(parsed.0, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn cow_str_owns_when_input_requires_unescaping() {
+        let input = r#""line\nbreak""#;
+        let parsed: CowStr = serde_json::from_str(input).unwrap();
+        assert_eq!(parsed.0, "line\nbreak");
+        assert!(matches!(parsed.0, Cow::Owned(_)));
+    }
+
+    #[test]
+    fn cow_bytes_round_trips_as_base64() {
+        let buf = CowBytes(Cow::Borrowed(&[1u8, 2, 3][..]));
+        let json = serde_json::to_string(&buf).unwrap();
+        assert_eq!(json, "\"AQID\"");
+
+        let decoded: CowBytes = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.0.as_ref(), &[1u8, 2, 3]);
+        assert!(matches!(decoded.0, Cow::Owned(_)));
+    }
+}
@@ -0,0 +1,538 @@
+/*
+ * Copyright (C) 2025 Movibase Platform Private Limited
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Decentralized, offline-verifiable per-topic authorization.
+//!
+//! A [`CapabilityToken`] is a chain of [`CapabilityLink`]s: the first link is
+//! issued by a root authority, and each subsequent link is a delegation from
+//! the previous link's audience to a new audience. Every link is signed
+//! (ECDSA P-256/SHA-256, mirroring [`SigningConfig::Es256`](super::signing::SigningConfig::Es256))
+//! over its own fields, so the chain can be verified by anyone who can
+//! resolve the issuing DIDs to public keys — no broker-side ACL lookup is
+//! required. [`CapabilityToken::verify`] checks that:
+//!
+//! - every link's signature verifies against its issuer's resolved key,
+//! - each link's `audience_did` equals the next link's `issuer_did`, and
+//! - each link's [`Capability`] attenuates (never broadens) the previous
+//!   link's, per [`Capability::attenuates`].
+//!
+//! `KafkaSerializer` attaches a producer's `CapabilityToken` to the envelope;
+//! `KafkaDeserializer` validates it against a `Topic::required_capability()`.
+
+use std::fmt;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use p256::ecdsa::signature::{Signer, Verifier};
+use p256::ecdsa::{Signature as EcdsaSignature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+/// Envelope object key `KafkaSerializer`/`KafkaDeserializer` nest the plain
+/// payload `Value` under, alongside [`CAPABILITY_TOKEN_KEY`], when a
+/// `capability_token` is attached.
+pub const CAPABILITY_BODY_KEY: &str = "@body";
+/// Envelope object key the serialized [`CapabilityToken`] is written to/read
+/// from, when one is attached.
+pub const CAPABILITY_TOKEN_KEY: &str = "@capability_token";
+
+/// Whether a capability authorizes producing to, or consuming from, a topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Produce,
+    Consume,
+}
+
+/// A grant over a `topic_pattern` (exact name, or a trailing-`*` prefix
+/// wildcard, e.g. `"application.communication.*"`) for one [`Action`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capability {
+    pub topic_pattern: String,
+    pub action: Action,
+}
+
+impl Capability {
+    /// Does this capability authorize `action` on the concrete topic
+    /// `topic_name`?
+    pub fn allows(&self, topic_name: &str, action: Action) -> bool {
+        self.action == action && pattern_matches(&self.topic_pattern, topic_name)
+    }
+
+    /// Does `self` attenuate (narrow or preserve, never broaden) `parent`?
+    ///
+    /// True when both grant the same `action` and every topic name `self`
+    /// matches is also matched by `parent` — i.e. delegation can only shrink
+    /// the pattern, never widen it.
+    pub fn attenuates(&self, parent: &Capability) -> bool {
+        self.action == parent.action && pattern_is_subset(&self.topic_pattern, &parent.topic_pattern)
+    }
+}
+
+/// Trailing-`*` prefix match: `"application.*"` matches `"application.foo"`;
+/// without a trailing `*`, the pattern must equal `name` exactly.
+fn pattern_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+/// Is every name matched by `narrower` also matched by `broader`?
+fn pattern_is_subset(narrower: &str, broader: &str) -> bool {
+    if narrower == broader {
+        return true;
+    }
+    let broader_prefix = match broader.strip_suffix('*') {
+        Some(prefix) => prefix,
+        None => return false, // an exact `broader` pattern can't be further narrowed
+    };
+    match narrower.strip_suffix('*') {
+        Some(narrower_prefix) => narrower_prefix.starts_with(broader_prefix),
+        None => narrower.starts_with(broader_prefix),
+    }
+}
+
+/// One link of delegation in a [`CapabilityToken`] chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityLink {
+    pub issuer_did: String,
+    pub audience_did: String,
+    pub capability: Capability,
+    /// Unix timestamp (seconds); the link is invalid before this instant.
+    pub not_before: i64,
+    /// Unix timestamp (seconds); the link is invalid at or after this instant.
+    pub expires: i64,
+    /// Base64url (no padding) ECDSA P-256/SHA-256 signature over
+    /// [`CapabilityLink::signing_input`], produced by `issuer_did`'s key.
+    pub proof_sig: String,
+}
+
+impl CapabilityLink {
+    /// The canonical bytes a link's `proof_sig` is computed over: everything
+    /// but the signature itself.
+    fn signing_input(&self) -> Result<Vec<u8>, serde_json::Error> {
+        #[derive(Serialize)]
+        struct Unsigned<'a> {
+            issuer_did: &'a str,
+            audience_did: &'a str,
+            capability: &'a Capability,
+            not_before: i64,
+            expires: i64,
+        }
+        serde_json::to_vec(&Unsigned {
+            issuer_did: &self.issuer_did,
+            audience_did: &self.audience_did,
+            capability: &self.capability,
+            not_before: self.not_before,
+            expires: self.expires,
+        })
+    }
+
+    /// Sign a freshly-built link's fields with the issuer's key, filling in
+    /// `proof_sig`.
+    pub fn sign(
+        issuer_did: String,
+        audience_did: String,
+        capability: Capability,
+        not_before: i64,
+        expires: i64,
+        issuer_key: &SigningKey,
+    ) -> Result<Self, CapabilityError> {
+        let mut link = CapabilityLink {
+            issuer_did,
+            audience_did,
+            capability,
+            not_before,
+            expires,
+            proof_sig: String::new(),
+        };
+        let signing_input = link.signing_input().map_err(CapabilityError::Json)?;
+        let signature: EcdsaSignature = issuer_key.sign(&signing_input);
+        link.proof_sig = URL_SAFE_NO_PAD.encode(signature.to_bytes());
+        Ok(link)
+    }
+}
+
+/// Resolves a DID to the public key it signs with. Implementations typically
+/// look up a DID document from a registry, cache, or well-known directory;
+/// this crate only consumes the resolved key.
+pub trait DidKeyResolver: fmt::Debug {
+    fn resolve(&self, did: &str) -> Option<VerifyingKey>;
+}
+
+/// A chain of delegated [`CapabilityLink`]s, root-first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityToken(pub Vec<CapabilityLink>);
+
+/// Errors verifying a [`CapabilityToken`].
+#[derive(Debug)]
+pub enum CapabilityError {
+    /// The token carries no links.
+    EmptyChain,
+    /// A link's `issuer_did` could not be resolved to a public key.
+    UnknownIssuer(String),
+    /// A link's signature does not verify against its issuer's key.
+    InvalidSignature,
+    /// A link's `not_before`/`expires` window does not cover the check time.
+    Expired,
+    /// A link's `audience_did` does not equal the next link's `issuer_did`.
+    ChainBroken,
+    /// A link's capability broadens (rather than attenuates) its parent's.
+    NotAttenuated,
+    /// The final capability does not authorize the requested topic/action.
+    Unauthorized,
+    /// The envelope carried no `@capability_token`, but one was required.
+    MissingToken,
+    /// `required_action` was set but no `did_resolver` was configured to
+    /// verify the token's issuer signatures against.
+    MissingResolver,
+    /// Failed to canonicalize a link's signing input.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for CapabilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapabilityError::EmptyChain => write!(f, "capability token has no links"),
+            CapabilityError::UnknownIssuer(did) => {
+                write!(f, "cannot resolve issuer did: {}", did)
+            }
+            CapabilityError::InvalidSignature => write!(f, "capability link signature is invalid"),
+            CapabilityError::Expired => write!(f, "capability link is outside its validity window"),
+            CapabilityError::ChainBroken => {
+                write!(f, "capability chain audience/issuer linkage is broken")
+            }
+            CapabilityError::NotAttenuated => {
+                write!(f, "capability delegation broadens its parent grant")
+            }
+            CapabilityError::Unauthorized => {
+                write!(f, "capability token does not authorize this topic/action")
+            }
+            CapabilityError::MissingToken => {
+                write!(f, "no capability token was attached, but one is required")
+            }
+            CapabilityError::MissingResolver => {
+                write!(f, "no did key resolver configured to verify the capability token")
+            }
+            CapabilityError::Json(e) => write!(f, "failed to canonicalize capability link: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CapabilityError {}
+
+impl CapabilityToken {
+    /// Verify every link's signature, the issuer/audience chain linkage, and
+    /// that each link attenuates the one before it. `now` is a Unix
+    /// timestamp (seconds). Returns the final (most specific) [`Capability`]
+    /// on success.
+    pub fn verify(
+        &self,
+        resolver: &dyn DidKeyResolver,
+        now: i64,
+    ) -> Result<&Capability, CapabilityError> {
+        if self.0.is_empty() {
+            return Err(CapabilityError::EmptyChain);
+        }
+
+        for (i, link) in self.0.iter().enumerate() {
+            if now < link.not_before || now >= link.expires {
+                return Err(CapabilityError::Expired);
+            }
+
+            let verifying_key = resolver
+                .resolve(&link.issuer_did)
+                .ok_or_else(|| CapabilityError::UnknownIssuer(link.issuer_did.clone()))?;
+            let signing_input = link.signing_input().map_err(CapabilityError::Json)?;
+            let signature = URL_SAFE_NO_PAD
+                .decode(&link.proof_sig)
+                .map_err(|_| CapabilityError::InvalidSignature)?;
+            let signature = EcdsaSignature::from_slice(&signature)
+                .map_err(|_| CapabilityError::InvalidSignature)?;
+            verifying_key
+                .verify(&signing_input, &signature)
+                .map_err(|_| CapabilityError::InvalidSignature)?;
+
+            if i > 0 {
+                let parent = &self.0[i - 1];
+                if parent.audience_did != link.issuer_did {
+                    return Err(CapabilityError::ChainBroken);
+                }
+                if !link.capability.attenuates(&parent.capability) {
+                    return Err(CapabilityError::NotAttenuated);
+                }
+            }
+        }
+
+        Ok(&self.0.last().expect("checked non-empty above").capability)
+    }
+
+    /// Verify the chain and confirm its final capability authorizes
+    /// `action` on `topic_name`.
+    pub fn authorizes(
+        &self,
+        resolver: &dyn DidKeyResolver,
+        now: i64,
+        topic_name: &str,
+        action: Action,
+    ) -> Result<(), CapabilityError> {
+        let final_capability = self.verify(resolver, now)?;
+        if final_capability.allows(topic_name, action) {
+            Ok(())
+        } else {
+            Err(CapabilityError::Unauthorized)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Debug)]
+    struct MapResolver(HashMap<String, VerifyingKey>);
+
+    impl DidKeyResolver for MapResolver {
+        fn resolve(&self, did: &str) -> Option<VerifyingKey> {
+            self.0.get(did).cloned()
+        }
+    }
+
+    fn keypair() -> (SigningKey, VerifyingKey) {
+        let signing_key = SigningKey::random(&mut rand::thread_rng());
+        let verifying_key = VerifyingKey::from(&signing_key);
+        (signing_key, verifying_key)
+    }
+
+    #[test]
+    fn pattern_matching_supports_trailing_wildcard() {
+        let cap = Capability {
+            topic_pattern: "application.communication.*".to_string(),
+            action: Action::Produce,
+        };
+        assert!(cap.allows("application.communication.messages", Action::Produce));
+        assert!(!cap.allows("application.communication.messages", Action::Consume));
+        assert!(!cap.allows("ops.application.logs", Action::Produce));
+    }
+
+    #[test]
+    fn attenuation_allows_narrowing_not_broadening() {
+        let broad = Capability {
+            topic_pattern: "application.communication.*".to_string(),
+            action: Action::Produce,
+        };
+        let narrow = Capability {
+            topic_pattern: "application.communication.messages".to_string(),
+            action: Action::Produce,
+        };
+        assert!(narrow.attenuates(&broad));
+        assert!(!broad.attenuates(&narrow));
+    }
+
+    #[test]
+    fn single_link_chain_verifies_and_authorizes() {
+        let (issuer_key, issuer_vk) = keypair();
+        let mut resolver = HashMap::new();
+        resolver.insert("did:monky:root".to_string(), issuer_vk);
+
+        let link = CapabilityLink::sign(
+            "did:monky:root".to_string(),
+            "did:monky:producer-a".to_string(),
+            Capability {
+                topic_pattern: "application.communication.*".to_string(),
+                action: Action::Produce,
+            },
+            0,
+            1_000_000_000,
+            &issuer_key,
+        )
+        .unwrap();
+        let token = CapabilityToken(vec![link]);
+
+        token
+            .authorizes(
+                &MapResolver(resolver),
+                500,
+                "application.communication.messages",
+                Action::Produce,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn delegated_chain_must_match_issuer_to_audience() {
+        let (root_key, root_vk) = keypair();
+        let (mid_key, mid_vk) = keypair();
+        let mut resolver = HashMap::new();
+        resolver.insert("did:monky:root".to_string(), root_vk);
+        resolver.insert("did:monky:mid".to_string(), mid_vk);
+
+        let root_link = CapabilityLink::sign(
+            "did:monky:root".to_string(),
+            "did:monky:mid".to_string(),
+            Capability {
+                topic_pattern: "application.communication.*".to_string(),
+                action: Action::Produce,
+            },
+            0,
+            1_000_000_000,
+            &root_key,
+        )
+        .unwrap();
+        let delegated_link = CapabilityLink::sign(
+            "did:monky:mid".to_string(),
+            "did:monky:producer-a".to_string(),
+            Capability {
+                topic_pattern: "application.communication.messages".to_string(),
+                action: Action::Produce,
+            },
+            0,
+            1_000_000_000,
+            &mid_key,
+        )
+        .unwrap();
+        let token = CapabilityToken(vec![root_link, delegated_link]);
+
+        token
+            .authorizes(
+                &MapResolver(resolver),
+                500,
+                "application.communication.messages",
+                Action::Produce,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn delegated_chain_rejects_broadened_capability() {
+        let (root_key, root_vk) = keypair();
+        let (mid_key, mid_vk) = keypair();
+        let mut resolver = HashMap::new();
+        resolver.insert("did:monky:root".to_string(), root_vk);
+        resolver.insert("did:monky:mid".to_string(), mid_vk);
+
+        let root_link = CapabilityLink::sign(
+            "did:monky:root".to_string(),
+            "did:monky:mid".to_string(),
+            Capability {
+                topic_pattern: "application.communication.messages".to_string(),
+                action: Action::Produce,
+            },
+            0,
+            1_000_000_000,
+            &root_key,
+        )
+        .unwrap();
+        let broadened_link = CapabilityLink::sign(
+            "did:monky:mid".to_string(),
+            "did:monky:producer-a".to_string(),
+            Capability {
+                topic_pattern: "application.communication.*".to_string(),
+                action: Action::Produce,
+            },
+            0,
+            1_000_000_000,
+            &mid_key,
+        )
+        .unwrap();
+        let token = CapabilityToken(vec![root_link, broadened_link]);
+
+        let err = token
+            .verify(&MapResolver(resolver), 500)
+            .unwrap_err();
+        assert!(matches!(err, CapabilityError::NotAttenuated));
+    }
+
+    #[test]
+    fn rejects_unresolvable_issuer() {
+        let (issuer_key, _issuer_vk) = keypair();
+        let link = CapabilityLink::sign(
+            "did:monky:root".to_string(),
+            "did:monky:producer-a".to_string(),
+            Capability {
+                topic_pattern: "application.communication.*".to_string(),
+                action: Action::Produce,
+            },
+            0,
+            1_000_000_000,
+            &issuer_key,
+        )
+        .unwrap();
+        let token = CapabilityToken(vec![link]);
+
+        let err = token
+            .verify(&MapResolver(HashMap::new()), 500)
+            .unwrap_err();
+        assert!(matches!(err, CapabilityError::UnknownIssuer(_)));
+    }
+
+    #[test]
+    fn rejects_expired_link() {
+        let (issuer_key, issuer_vk) = keypair();
+        let mut resolver = HashMap::new();
+        resolver.insert("did:monky:root".to_string(), issuer_vk);
+
+        let link = CapabilityLink::sign(
+            "did:monky:root".to_string(),
+            "did:monky:producer-a".to_string(),
+            Capability {
+                topic_pattern: "application.communication.*".to_string(),
+                action: Action::Produce,
+            },
+            0,
+            1000,
+            &issuer_key,
+        )
+        .unwrap();
+        let token = CapabilityToken(vec![link]);
+
+        let err = token
+            .verify(&MapResolver(resolver), 5000)
+            .unwrap_err();
+        assert!(matches!(err, CapabilityError::Expired));
+    }
+
+    #[test]
+    fn rejects_wrong_action_on_authorization_check() {
+        let (issuer_key, issuer_vk) = keypair();
+        let mut resolver = HashMap::new();
+        resolver.insert("did:monky:root".to_string(), issuer_vk);
+
+        let link = CapabilityLink::sign(
+            "did:monky:root".to_string(),
+            "did:monky:consumer-a".to_string(),
+            Capability {
+                topic_pattern: "application.communication.*".to_string(),
+                action: Action::Consume,
+            },
+            0,
+            1_000_000_000,
+            &issuer_key,
+        )
+        .unwrap();
+        let token = CapabilityToken(vec![link]);
+
+        let err = token
+            .authorizes(
+                &MapResolver(resolver),
+                500,
+                "application.communication.messages",
+                Action::Produce,
+            )
+            .unwrap_err();
+        assert!(matches!(err, CapabilityError::Unauthorized));
+    }
+}
@@ -19,9 +19,14 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::{Map, Value};
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
 
+use crate::signature::{get_signature, verify_signature, Algorithm, HmacError};
+
+use super::signing::{EncryptionConfig, SigningConfig};
+use super::wire_format::WireFormatKind;
+
 /// Control how type metadata is emitted.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TypeTagging {
@@ -43,6 +48,17 @@ pub struct HybridObjectMapper {
     /// Optional set of type names to ignore (for dynamic JSON payloads) - those entries
     /// are dropped or replaced with `null` depending on context.
     pub ignore_type_names: HashSet<String>,
+    /// When set, `KafkaSerializer`/`KafkaDeserializer` wrap the payload in a
+    /// JWS-compact `header.payload.signature` envelope signed/verified per
+    /// this configuration instead of writing raw JSON.
+    pub signing: Option<SigningConfig>,
+    /// When set, `KafkaSerializer`/`KafkaDeserializer` encrypt/decrypt the
+    /// payload with this JWE-style "direct" AES-256-GCM configuration.
+    pub encryption: Option<EncryptionConfig>,
+    /// Which `WireFormat` `KafkaSerializer`/`KafkaDeserializer` use to encode
+    /// the plain (unsigned, unencrypted) payload body. Ignored by the
+    /// `signing`/`encryption` envelope modes, which always carry JSON.
+    pub wire_format: WireFormatKind,
 }
 
 impl Default for HybridObjectMapper {
@@ -51,6 +67,9 @@ impl Default for HybridObjectMapper {
             type_tagging: TypeTagging::None,
             omit_null_values: true,
             ignore_type_names: HashSet::new(),
+            signing: None,
+            encryption: None,
+            wire_format: WireFormatKind::default(),
         }
     }
 }
@@ -121,6 +140,13 @@ impl HybridObjectMapper {
     /// from the `value` field. Otherwise it tries to deserialize the whole payload.
     pub fn deserialize_with_type<T: DeserializeOwned>(&self, s: &str) -> Result<T, serde_json::Error> {
         let v: Value = serde_json::from_str(s)?;
+        self.unwrap_type_tagged(v)
+    }
+
+    /// Like `deserialize_with_type`, but operates on an already-parsed
+    /// `Value` rather than JSON text — used by `KafkaDeserializer` once a
+    /// `WireFormat` has decoded the payload bytes into a `Value`.
+    pub fn unwrap_type_tagged<T: DeserializeOwned>(&self, v: Value) -> Result<T, serde_json::Error> {
         if let Value::Object(mut m) = v {
             if let Some(Value::String(_tn)) = m.get("@type") {
                 if let Some(val) = m.remove("value") {
@@ -156,6 +182,115 @@ impl HybridObjectMapper {
             }
         }
     }
+
+    /// Serialize `value` through the mapper's normal pipeline, then attach an
+    /// HMAC-SHA256 signature computed over the canonical JSON bytes.
+    ///
+    /// Object keys are sorted recursively before signing so that producer and
+    /// consumer hash identical bytes regardless of serde map ordering. The
+    /// returned [`SignedEnvelope::body`] is the canonical JSON to put on the
+    /// wire, and [`SignedEnvelope::signature_header`] is the value to place
+    /// in `CONTENT_SIGNATURE_HEADER`.
+    pub fn serialize_signed<T: Serialize>(
+        &self,
+        value: &T,
+        key: &str,
+    ) -> Result<SignedEnvelope, MapperSigningError> {
+        let mut v = self.to_json_value(value)?;
+        if let TypeTagging::Adjacent = self.type_tagging {
+            let mut map = Map::with_capacity(2);
+            map.insert("@type".to_string(), Value::String(infer_type_name::<T>()));
+            map.insert("value".to_string(), v);
+            v = Value::Object(map);
+        }
+        let body = serde_json::to_string(&canonicalize(v))?;
+        let signature_header = get_signature(key, &body)?;
+        Ok(SignedEnvelope {
+            body,
+            signature_header,
+        })
+    }
+
+    /// Verify `provided_sig` against the HMAC-SHA256 of the exact received
+    /// `body` bytes, then deserialize `body` through `deserialize_with_type`.
+    ///
+    /// The comparison is constant-time. Deserialization only happens once the
+    /// signature is confirmed to match.
+    pub fn deserialize_verified<T: DeserializeOwned>(
+        &self,
+        body: &str,
+        provided_sig: &str,
+        key: &str,
+    ) -> Result<T, MapperSigningError> {
+        if !verify_signature(Algorithm::HmacSha256, key, body, provided_sig)? {
+            return Err(MapperSigningError::SignatureMismatch);
+        }
+        self.deserialize_with_type(body).map_err(MapperSigningError::Json)
+    }
+}
+
+/// The result of [`HybridObjectMapper::serialize_signed`]: the canonical JSON
+/// body and the signature to carry alongside it (e.g. in
+/// `CONTENT_SIGNATURE_HEADER`).
+#[derive(Debug, Clone)]
+pub struct SignedEnvelope {
+    /// Canonical (sorted-key) JSON body that was signed.
+    pub body: String,
+    /// Lowercase hex HMAC-SHA256 signature of `body`.
+    pub signature_header: String,
+}
+
+/// Errors from the signed-envelope serialize/deserialize pair.
+#[derive(Debug)]
+pub enum MapperSigningError {
+    /// JSON (de)serialization failed.
+    Json(serde_json::Error),
+    /// The HMAC key or algorithm was invalid.
+    Hmac(HmacError),
+    /// The recomputed signature did not match the provided one.
+    SignatureMismatch,
+}
+
+impl fmt::Display for MapperSigningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapperSigningError::Json(e) => write!(f, "signed envelope json error: {}", e),
+            MapperSigningError::Hmac(e) => write!(f, "signed envelope hmac error: {}", e),
+            MapperSigningError::SignatureMismatch => {
+                write!(f, "signed envelope signature mismatch")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MapperSigningError {}
+
+impl From<serde_json::Error> for MapperSigningError {
+    fn from(e: serde_json::Error) -> Self {
+        MapperSigningError::Json(e)
+    }
+}
+
+impl From<HmacError> for MapperSigningError {
+    fn from(e: HmacError) -> Self {
+        MapperSigningError::Hmac(e)
+    }
+}
+
+/// Recursively sort object keys so that two semantically-equal JSON values
+/// serialize to identical bytes regardless of insertion order.
+fn canonicalize(v: Value) -> Value {
+    match v {
+        Value::Object(map) => {
+            let sorted: BTreeMap<String, Value> =
+                map.into_iter().map(|(k, val)| (k, canonicalize(val))).collect();
+            let mut out = Map::with_capacity(sorted.len());
+            out.extend(sorted);
+            Value::Object(out)
+        }
+        Value::Array(arr) => Value::Array(arr.into_iter().map(canonicalize).collect()),
+        other => other,
+    }
 }
 
 /// Remove all `null` entries from objects and arrays recursively.
@@ -226,6 +361,9 @@ mod tests {
             type_tagging: TypeTagging::None,
             omit_null_values: true,
             ignore_type_names: HashSet::new(),
+            signing: None,
+            encryption: None,
+            wire_format: WireFormatKind::default(),
         };
         let s = Sample {
             a: "x".into(),
@@ -244,6 +382,9 @@ mod tests {
             type_tagging: TypeTagging::Adjacent,
             omit_null_values: true,
             ignore_type_names: HashSet::new(),
+            signing: None,
+            encryption: None,
+            wire_format: WireFormatKind::default(),
         };
         let s = Sample {
             a: "y".into(),
@@ -285,4 +426,39 @@ mod tests {
         assert!(filtered.get("org.apache.avro.Schema").is_none());
         assert_eq!(filtered.get("keep").unwrap(), "x");
     }
+
+    #[derive(Serialize)]
+    struct Ordered {
+        z: i32,
+        a: i32,
+        m: i32,
+    }
+
+    #[test]
+    fn serialize_signed_round_trips_through_deserialize_verified() {
+        let mapper = HybridObjectMapper::new();
+        let value = Ordered { z: 1, a: 2, m: 3 };
+
+        let envelope = mapper.serialize_signed(&value, "secret").unwrap();
+        // Canonicalization sorts keys regardless of struct field order.
+        assert!(envelope.body.find("\"a\"").unwrap() < envelope.body.find("\"m\"").unwrap());
+        assert!(envelope.body.find("\"m\"").unwrap() < envelope.body.find("\"z\"").unwrap());
+
+        let decoded: Value = mapper
+            .deserialize_verified(&envelope.body, &envelope.signature_header, "secret")
+            .unwrap();
+        assert_eq!(decoded, json!({"a": 2, "m": 3, "z": 1}));
+    }
+
+    #[test]
+    fn deserialize_verified_rejects_tampered_body() {
+        let mapper = HybridObjectMapper::new();
+        let value = Ordered { z: 1, a: 2, m: 3 };
+        let envelope = mapper.serialize_signed(&value, "secret").unwrap();
+
+        let tampered_body = envelope.body.replace("\"a\":2", "\"a\":99");
+        let result: Result<Value, MapperSigningError> =
+            mapper.deserialize_verified(&tampered_body, &envelope.signature_header, "secret");
+        assert!(matches!(result, Err(MapperSigningError::SignatureMismatch)));
+    }
 }
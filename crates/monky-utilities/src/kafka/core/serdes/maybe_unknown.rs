@@ -0,0 +1,89 @@
+/*
+ * Copyright (C) 2025 Movibase Platform Private Limited
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Forward-compatible handling for string-tagged enums.
+//!
+//! A producer running a newer schema version may emit a variant a consumer
+//! on an older schema version doesn't know about yet. Wrapping the field in
+//! [`MaybeUnknown<T>`] turns that from a hard deserialization failure into a
+//! [`MaybeUnknown::Unknown`] carrying the raw tag, so the rest of the message
+//! still deserializes.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// Either a known `T`, or a string tag that didn't match any variant of `T`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MaybeUnknown<T> {
+    Known(T),
+    Unknown(String),
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for MaybeUnknown<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        match T::deserialize(value.clone()) {
+            Ok(known) => Ok(MaybeUnknown::Known(known)),
+            Err(_) => match value {
+                Value::String(tag) => Ok(MaybeUnknown::Unknown(tag)),
+                other => Ok(MaybeUnknown::Unknown(other.to_string())),
+            },
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for MaybeUnknown<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            MaybeUnknown::Known(known) => known.serialize(serializer),
+            MaybeUnknown::Unknown(tag) => serializer.serialize_str(tag),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    enum Color {
+        Red,
+        Green,
+        Blue,
+    }
+
+    #[test]
+    fn known_variant_deserializes_as_known() {
+        let parsed: MaybeUnknown<Color> = serde_json::from_str("\"Red\"").unwrap();
+        assert_eq!(parsed, MaybeUnknown::Known(Color::Red));
+    }
+
+    #[test]
+    fn unrecognized_variant_falls_back_to_unknown() {
+        let parsed: MaybeUnknown<Color> = serde_json::from_str("\"Ultraviolet\"").unwrap();
+        assert_eq!(parsed, MaybeUnknown::Unknown("Ultraviolet".to_string()));
+    }
+
+    #[test]
+    fn serialize_round_trips_each_variant() {
+        let known = MaybeUnknown::Known(Color::Blue);
+        assert_eq!(serde_json::to_string(&known).unwrap(), "\"Blue\"");
+
+        let unknown: MaybeUnknown<Color> = MaybeUnknown::Unknown("Ultraviolet".to_string());
+        assert_eq!(serde_json::to_string(&unknown).unwrap(), "\"Ultraviolet\"");
+    }
+}
@@ -0,0 +1,247 @@
+/*
+ * Copyright (C) 2025 Movibase Platform Private Limited
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Pluggable wire-format backends for `KafkaSerializer`/`KafkaDeserializer`.
+//!
+//! The plain (unsigned, unencrypted) payload body is encoded/decoded through
+//! a [`WireFormat`] chosen by [`HybridObjectMapper::wire_format`](super::hybrid_object_mapper::HybridObjectMapper::wire_format).
+//! Every format operates on an already-built `serde_json::Value` — the common
+//! representation `KafkaSerializer` builds with `HybridObjectMapper::to_json_value`
+//! (which applies `omit_null_values`) and then wraps for adjacent type
+//! tagging when configured — so adjacent-tag wrapping stays a
+//! single code path regardless of which format ends up on the wire. The
+//! format's [`WireFormat::id`] is written to the wire as a single byte right
+//! after the schema-version byte.
+
+use std::fmt;
+use url::form_urlencoded;
+
+use serde_json::{Map, Value};
+
+/// A wire encoding for the JSON `Value` produced by `HybridObjectMapper`.
+///
+/// Implementations operate on `Value` rather than a generic `T` so that a
+/// `KafkaSerializer`/`KafkaDeserializer` can select one at runtime by its
+/// [`WireFormat::id`] byte.
+pub trait WireFormat: fmt::Debug {
+    /// Single-byte identifier for this format, written to the wire
+    /// immediately after the schema-version byte.
+    fn id(&self) -> u8;
+
+    /// Encode `value` into its wire representation.
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, WireFormatError>;
+
+    /// Decode a wire representation produced by [`WireFormat::encode`] back
+    /// into a `Value`.
+    fn decode(&self, bytes: &[u8]) -> Result<Value, WireFormatError>;
+}
+
+/// Errors from encoding/decoding through a [`WireFormat`].
+#[derive(Debug)]
+pub enum WireFormatError {
+    Json(serde_json::Error),
+    Cbor(String),
+    /// The payload was not valid UTF-8 (url-encoded format only).
+    InvalidUtf8(std::str::Utf8Error),
+}
+
+impl fmt::Display for WireFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WireFormatError::Json(e) => write!(f, "json wire-format error: {}", e),
+            WireFormatError::Cbor(e) => write!(f, "cbor wire-format error: {}", e),
+            WireFormatError::InvalidUtf8(e) => {
+                write!(f, "url-encoded wire-format is not valid utf-8: {}", e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WireFormatError {}
+
+impl From<serde_json::Error> for WireFormatError {
+    fn from(e: serde_json::Error) -> Self {
+        WireFormatError::Json(e)
+    }
+}
+
+impl From<std::str::Utf8Error> for WireFormatError {
+    fn from(e: std::str::Utf8Error) -> Self {
+        WireFormatError::InvalidUtf8(e)
+    }
+}
+
+/// Plain JSON, as produced by `serde_json`. The default format.
+#[derive(Debug, Clone, Copy)]
+pub struct JsonFormat;
+
+impl WireFormat for JsonFormat {
+    fn id(&self) -> u8 {
+        0
+    }
+
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, WireFormatError> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, WireFormatError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// `application/x-www-form-urlencoded`. Only sensible for a top-level JSON
+/// object whose values are scalars; nested objects/arrays are flattened to
+/// their compact JSON text, and every decoded value comes back as a string
+/// (the format itself is untyped).
+#[derive(Debug, Clone, Copy)]
+pub struct UrlEncodedFormat;
+
+impl WireFormat for UrlEncodedFormat {
+    fn id(&self) -> u8 {
+        1
+    }
+
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, WireFormatError> {
+        let mut serializer = form_urlencoded::Serializer::new(String::new());
+        if let Value::Object(map) = value {
+            for (k, v) in map {
+                serializer.append_pair(k, &scalar_to_string(v));
+            }
+        } else {
+            serializer.append_pair("value", &scalar_to_string(value));
+        }
+        Ok(serializer.finish().into_bytes())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, WireFormatError> {
+        let s = std::str::from_utf8(bytes)?;
+        let mut map = Map::with_capacity(8);
+        for (k, v) in form_urlencoded::parse(s.as_bytes()) {
+            map.insert(k.into_owned(), Value::String(v.into_owned()));
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+/// Stringify a `Value` for use as a url-encoded field value: plain strings
+/// pass through as-is, everything else becomes its compact JSON text.
+fn scalar_to_string(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// CBOR (RFC 8949), via `ciborium`.
+#[derive(Debug, Clone, Copy)]
+pub struct CborFormat;
+
+impl WireFormat for CborFormat {
+    fn id(&self) -> u8 {
+        2
+    }
+
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, WireFormatError> {
+        let mut out = Vec::new();
+        ciborium::into_writer(value, &mut out).map_err(|e| WireFormatError::Cbor(e.to_string()))?;
+        Ok(out)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, WireFormatError> {
+        ciborium::from_reader(bytes).map_err(|e| WireFormatError::Cbor(e.to_string()))
+    }
+}
+
+const JSON_FORMAT: JsonFormat = JsonFormat;
+const URL_ENCODED_FORMAT: UrlEncodedFormat = UrlEncodedFormat;
+const CBOR_FORMAT: CborFormat = CborFormat;
+
+/// Which [`WireFormat`] a `HybridObjectMapper` should use for the plain
+/// (unsigned, unencrypted) payload body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireFormatKind {
+    #[default]
+    Json,
+    UrlEncoded,
+    Cbor,
+}
+
+/// Resolve a [`WireFormatKind`] to its [`WireFormat`] implementation.
+pub fn format_for(kind: WireFormatKind) -> &'static dyn WireFormat {
+    match kind {
+        WireFormatKind::Json => &JSON_FORMAT,
+        WireFormatKind::UrlEncoded => &URL_ENCODED_FORMAT,
+        WireFormatKind::Cbor => &CBOR_FORMAT,
+    }
+}
+
+/// Resolve a wire-carried format-id byte back to its [`WireFormat`]
+/// implementation. Returns `None` for an id no known format claims.
+pub fn format_for_id(id: u8) -> Option<&'static dyn WireFormat> {
+    match id {
+        0 => Some(&JSON_FORMAT),
+        1 => Some(&URL_ENCODED_FORMAT),
+        2 => Some(&CBOR_FORMAT),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn json_format_round_trips() {
+        let value = json!({"k": "v", "n": 3});
+        let encoded = JsonFormat.encode(&value).unwrap();
+        let decoded = JsonFormat.decode(&encoded).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn url_encoded_format_round_trips_scalars() {
+        let value = json!({"name": "alice", "age": 30});
+        let encoded = UrlEncodedFormat.encode(&value).unwrap();
+        let decoded = UrlEncodedFormat.decode(&encoded).unwrap();
+        assert_eq!(decoded["name"], "alice");
+        assert_eq!(decoded["age"], "30");
+    }
+
+    #[test]
+    fn cbor_format_round_trips() {
+        let value = json!({"k": "v", "n": 3, "nested": {"a": [1, 2, 3]}});
+        let encoded = CborFormat.encode(&value).unwrap();
+        let decoded = CborFormat.decode(&encoded).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn format_for_id_resolves_all_known_ids() {
+        assert_eq!(format_for_id(0).unwrap().id(), 0);
+        assert_eq!(format_for_id(1).unwrap().id(), 1);
+        assert_eq!(format_for_id(2).unwrap().id(), 2);
+        assert!(format_for_id(99).is_none());
+    }
+
+    #[test]
+    fn format_for_matches_kind_to_id() {
+        assert_eq!(format_for(WireFormatKind::Json).id(), 0);
+        assert_eq!(format_for(WireFormatKind::UrlEncoded).id(), 1);
+        assert_eq!(format_for(WireFormatKind::Cbor).id(), 2);
+    }
+}
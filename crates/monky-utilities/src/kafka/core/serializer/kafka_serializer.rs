@@ -15,12 +15,16 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use serde::Serialize;
 use std::{error::Error, fmt, io};
 
 use crate::kafka::core::{
     MONKY_MAGIC_BYTE,
+    serdes::capability::{CapabilityToken, CAPABILITY_BODY_KEY, CAPABILITY_TOKEN_KEY},
     serdes::hybrid_object_mapper::{HybridObjectMapper, TypeTagging},
+    serdes::signing,
+    serdes::wire_format::{self, JsonFormat, WireFormat, WireFormatError, WireFormatKind},
 };
 
 /// Errors for serialization (no external crates).
@@ -28,6 +32,13 @@ use crate::kafka::core::{
 pub enum SerializationError {
     Io(io::Error),
     Json(serde_json::Error),
+    WireFormat(WireFormatError),
+    /// `capability_token` is set but `self.mapper` also has `encryption` or
+    /// `signing` configured. The token would either have to be nested inside
+    /// an opaque JWE/JWS envelope (unreadable to a consumer that hasn't
+    /// decrypted/verified yet) or be silently dropped, so this combination
+    /// is rejected outright instead.
+    CapabilityUnsupportedWithEnvelope,
 }
 
 impl fmt::Display for SerializationError {
@@ -35,6 +46,11 @@ impl fmt::Display for SerializationError {
         match self {
             SerializationError::Io(e) => write!(f, "io error during serialization: {}", e),
             SerializationError::Json(e) => write!(f, "json serialization error: {}", e),
+            SerializationError::WireFormat(e) => write!(f, "wire-format error: {}", e),
+            SerializationError::CapabilityUnsupportedWithEnvelope => write!(
+                f,
+                "capability_token is not supported together with signing or encryption"
+            ),
         }
     }
 }
@@ -44,6 +60,8 @@ impl Error for SerializationError {
         match self {
             SerializationError::Io(e) => Some(e),
             SerializationError::Json(e) => Some(e),
+            SerializationError::WireFormat(e) => Some(e),
+            SerializationError::CapabilityUnsupportedWithEnvelope => None,
         }
     }
 }
@@ -60,42 +78,140 @@ impl From<serde_json::Error> for SerializationError {
     }
 }
 
-/// Stateless serializer. It prepends `MONKY_MAGIC_BYTE` and writes the JSON serialization of `data`.
+impl From<WireFormatError> for SerializationError {
+    fn from(e: WireFormatError) -> Self {
+        SerializationError::WireFormat(e)
+    }
+}
+
+/// The schema-version byte written directly after `MONKY_MAGIC_BYTE` by a
+/// `KafkaSerializer` that hasn't been given an explicit `schema_version`.
+pub const CURRENT_SCHEMA_VERSION: u8 = 0;
+
+/// Stateless serializer. It prepends `MONKY_MAGIC_BYTE` followed by a
+/// schema-version byte and a format-id byte, then writes `data` encoded with
+/// the mapper's `WireFormat` (or as a JSON signing/encryption envelope).
 #[derive(Debug, Default)]
 pub struct KafkaSerializer {
     mapper: HybridObjectMapper,
+    /// Schema-version byte written after `MONKY_MAGIC_BYTE`. Defaults to
+    /// [`CURRENT_SCHEMA_VERSION`].
+    pub schema_version: u8,
+    /// Delegable capability token to attach to the envelope, proving this
+    /// producer is authorized for the target `Topic`. Only supported on the
+    /// plain (non-signed, non-encrypted) payload path — combining this with
+    /// `self.mapper.signing`/`self.mapper.encryption` is rejected by
+    /// [`KafkaSerializer::serialize`] with
+    /// `SerializationError::CapabilityUnsupportedWithEnvelope`.
+    pub capability_token: Option<CapabilityToken>,
 }
 
 impl KafkaSerializer {
-    /// Create a default serializer using a default-configured `HybridObjectMapper`.
+    /// Create a default serializer using a default-configured `HybridObjectMapper`
+    /// and [`CURRENT_SCHEMA_VERSION`].
     pub fn new() -> Self {
         KafkaSerializer {
             mapper: HybridObjectMapper::new(),
+            schema_version: CURRENT_SCHEMA_VERSION,
+            capability_token: None,
         }
     }
 
-    /// Create with a preconfigured mapper.
+    /// Create with a preconfigured mapper. Uses [`CURRENT_SCHEMA_VERSION`].
     pub fn with_mapper(mapper: HybridObjectMapper) -> Self {
-        KafkaSerializer { mapper }
+        KafkaSerializer {
+            mapper,
+            schema_version: CURRENT_SCHEMA_VERSION,
+            capability_token: None,
+        }
     }
 
-    /// Serialize `data` into a `Vec<u8>` that begins with the magic byte.
+    /// Nest `value` under [`CAPABILITY_BODY_KEY`] alongside the attached
+    /// `capability_token` under [`CAPABILITY_TOKEN_KEY`], if one is set.
+    fn attach_capability_token(
+        &self,
+        value: serde_json::Value,
+    ) -> Result<serde_json::Value, SerializationError> {
+        let Some(token) = &self.capability_token else {
+            return Ok(value);
+        };
+        let mut map = serde_json::map::Map::with_capacity(2);
+        map.insert(CAPABILITY_TOKEN_KEY.to_string(), serde_json::to_value(token)?);
+        map.insert(CAPABILITY_BODY_KEY.to_string(), value);
+        Ok(serde_json::Value::Object(map))
+    }
+
+    /// Serialize `data` into a `Vec<u8>` that begins with the magic byte,
+    /// `self.schema_version`, and a format-id byte identifying the
+    /// `WireFormat` the rest of the payload is encoded with.
     ///
     /// `topic` is accepted for API parity but currently unused.
+    ///
+    /// Returns `SerializationError::CapabilityUnsupportedWithEnvelope` if
+    /// `capability_token` is set together with `self.mapper.encryption` or
+    /// `self.mapper.signing` — see [`Self::attach_capability_token`].
     pub fn serialize<T: Serialize>(
         &self,
         _topic: &str,
         data: &T,
     ) -> Result<Vec<u8>, SerializationError> {
-        // Fast path: no tagging, no null filtering, stream directly
-        if self.mapper.type_tagging == TypeTagging::None && !self.mapper.omit_null_values {
+        if self.capability_token.is_some()
+            && (self.mapper.encryption.is_some() || self.mapper.signing.is_some())
+        {
+            return Err(SerializationError::CapabilityUnsupportedWithEnvelope);
+        }
+
+        // JWE-style encryption takes priority: the whole envelope becomes
+        // `header_b64.ciphertext_b64` after the three header bytes. The envelope body
+        // is always JSON text regardless of `self.mapper.wire_format`.
+        if let Some(encryption) = &self.mapper.encryption {
+            let value = self.mapper.to_json_value(data)?;
+            let plaintext = serde_json::to_vec(&value)?;
+            let envelope = signing::encrypt_jwe(&plaintext, encryption);
+            let header_b64 = URL_SAFE_NO_PAD.encode(envelope.header_json.as_bytes());
+            let body = format!("{}.{}", header_b64, envelope.ciphertext_b64);
+
+            let mut out = Vec::with_capacity(body.len() + 3);
+            out.push(MONKY_MAGIC_BYTE);
+            out.push(self.schema_version);
+            out.push(JsonFormat.id());
+            out.extend_from_slice(body.as_bytes());
+            return Ok(out);
+        }
+
+        // JWS-style signing: the envelope becomes `header.payload.signature`
+        // after the three header bytes. Same JSON-only rule as the encryption branch.
+        if let Some(signing_config) = &self.mapper.signing {
+            let value = self.mapper.to_json_value(data)?;
+            let payload_json = serde_json::to_vec(&value)?;
+            let token = signing::encode_jws(&payload_json, signing_config);
+
+            let mut out = Vec::with_capacity(token.len() + 3);
+            out.push(MONKY_MAGIC_BYTE);
+            out.push(self.schema_version);
+            out.push(JsonFormat.id());
+            out.extend_from_slice(token.as_bytes());
+            return Ok(out);
+        }
+
+        // Fast path: plain JSON, no tagging, no null filtering, no
+        // capability token, stream directly.
+        if self.mapper.wire_format == WireFormatKind::Json
+            && self.mapper.type_tagging == TypeTagging::None
+            && !self.mapper.omit_null_values
+            && self.capability_token.is_none()
+        {
             let mut out = Vec::with_capacity(1024);
             out.push(MONKY_MAGIC_BYTE);
+            out.push(self.schema_version);
+            out.push(JsonFormat.id());
             serde_json::to_writer(&mut out, data)?;
             return Ok(out);
         }
 
-        // Adjacent type tagging: wrap data and stream value
+        let format = wire_format::format_for(self.mapper.wire_format);
+
+        // Adjacent type tagging: wrap data, then encode through the format.
         if self.mapper.type_tagging == TypeTagging::Adjacent {
             let payload_value = self.mapper.to_json_value(data)?;
             let mut map = serde_json::map::Map::with_capacity(2);
@@ -105,18 +221,26 @@ impl KafkaSerializer {
             );
             map.insert("value".to_string(), payload_value);
             let wrapped = serde_json::Value::Object(map);
+            let wrapped = self.attach_capability_token(wrapped)?;
 
-            let mut out = Vec::with_capacity(1024);
+            let encoded = format.encode(&wrapped)?;
+            let mut out = Vec::with_capacity(encoded.len() + 3);
             out.push(MONKY_MAGIC_BYTE);
-            serde_json::to_writer(&mut out, &wrapped)?;
+            out.push(self.schema_version);
+            out.push(format.id());
+            out.extend_from_slice(&encoded);
             return Ok(out);
         }
 
-        // Default: apply omit_null_values, then stream
+        // Default: apply omit_null_values, then encode through the format.
         let value = self.mapper.to_json_value(data)?;
-        let mut out = Vec::with_capacity(1024);
+        let value = self.attach_capability_token(value)?;
+        let encoded = format.encode(&value)?;
+        let mut out = Vec::with_capacity(encoded.len() + 3);
         out.push(MONKY_MAGIC_BYTE);
-        serde_json::to_writer(&mut out, &value)?;
+        out.push(self.schema_version);
+        out.push(format.id());
+        out.extend_from_slice(&encoded);
         Ok(out)
     }
 }
@@ -146,7 +270,9 @@ mod tests {
 
         let bytes = ser.serialize("topic", &item).expect("serialize");
         assert_eq!(bytes[0], MONKY_MAGIC_BYTE);
-        let json_part = &bytes[1..];
+        assert_eq!(bytes[1], CURRENT_SCHEMA_VERSION);
+        assert_eq!(bytes[2], JsonFormat.id());
+        let json_part = &bytes[3..];
         let v: serde_json::Value = serde_json::from_slice(json_part).expect("parse json");
         assert_eq!(v["id"], 1);
         assert_eq!(v["name"], "alice");
@@ -164,8 +290,10 @@ mod tests {
 
         let bytes = ser.serialize("t", &map).expect("serialize");
         assert_eq!(bytes[0], MONKY_MAGIC_BYTE);
+        assert_eq!(bytes[1], CURRENT_SCHEMA_VERSION);
+        assert_eq!(bytes[2], JsonFormat.id());
 
-        let v: serde_json::Value = serde_json::from_slice(&bytes[1..]).unwrap();
+        let v: serde_json::Value = serde_json::from_slice(&bytes[3..]).unwrap();
         assert!(v.get("@type").is_some());
         assert!(v.get("value").is_some());
     }
@@ -176,7 +304,171 @@ mod tests {
         let ser = KafkaSerializer::new();
         let arr = AvroGenericArray(vec![json!(1), json!(null), json!(2)]);
         let bytes = ser.serialize("t", &arr).expect("serialize");
-        let v: serde_json::Value = serde_json::from_slice(&bytes[1..]).unwrap();
+        let v: serde_json::Value = serde_json::from_slice(&bytes[3..]).unwrap();
         assert_eq!(v, json!([1, 2]));
     }
+
+    #[test]
+    fn serialize_with_hs256_signing_produces_jws_body() {
+        let mut mapper = HybridObjectMapper::new();
+        mapper.signing = Some(signing::SigningConfig::Hs256 {
+            key: b"secret".to_vec(),
+        });
+        let ser = KafkaSerializer::with_mapper(mapper);
+
+        let item = Item {
+            id: 1,
+            name: "alice".to_string(),
+            optional: None,
+        };
+        let bytes = ser.serialize("topic", &item).expect("serialize");
+        assert_eq!(bytes[0], MONKY_MAGIC_BYTE);
+        assert_eq!(bytes[1], CURRENT_SCHEMA_VERSION);
+        assert_eq!(bytes[2], JsonFormat.id());
+
+        let body = std::str::from_utf8(&bytes[3..]).unwrap();
+        assert_eq!(body.matches('.').count(), 2);
+    }
+
+    #[test]
+    fn serialize_with_encryption_produces_two_part_body() {
+        let mut mapper = HybridObjectMapper::new();
+        mapper.encryption = Some(signing::EncryptionConfig { key: [7u8; 32] });
+        let ser = KafkaSerializer::with_mapper(mapper);
+
+        let item = Item {
+            id: 1,
+            name: "alice".to_string(),
+            optional: None,
+        };
+        let bytes = ser.serialize("topic", &item).expect("serialize");
+        assert_eq!(bytes[0], MONKY_MAGIC_BYTE);
+        assert_eq!(bytes[1], CURRENT_SCHEMA_VERSION);
+        assert_eq!(bytes[2], JsonFormat.id());
+
+        let body = std::str::from_utf8(&bytes[3..]).unwrap();
+        assert_eq!(body.matches('.').count(), 1);
+    }
+
+    #[test]
+    fn serialize_with_non_default_schema_version() {
+        let mut ser = KafkaSerializer::new();
+        ser.schema_version = 3;
+
+        let item = Item {
+            id: 1,
+            name: "alice".to_string(),
+            optional: None,
+        };
+        let bytes = ser.serialize("topic", &item).expect("serialize");
+        assert_eq!(bytes[0], MONKY_MAGIC_BYTE);
+        assert_eq!(bytes[1], 3);
+    }
+
+    #[test]
+    fn serialize_with_capability_token_nests_token_and_body() {
+        use crate::kafka::core::serdes::capability::{Action, Capability, CapabilityLink, CapabilityToken};
+        use p256::ecdsa::SigningKey;
+
+        let issuer_key = SigningKey::random(&mut rand::thread_rng());
+        let link = CapabilityLink::sign(
+            "did:monky:root".to_string(),
+            "did:monky:producer-a".to_string(),
+            Capability {
+                topic_pattern: "application.communication.*".to_string(),
+                action: Action::Produce,
+            },
+            0,
+            1_000_000_000,
+            &issuer_key,
+        )
+        .unwrap();
+
+        let mut ser = KafkaSerializer::new();
+        ser.capability_token = Some(CapabilityToken(vec![link]));
+
+        let item = Item {
+            id: 1,
+            name: "alice".to_string(),
+            optional: None,
+        };
+        let bytes = ser.serialize("topic", &item).expect("serialize");
+        let v: serde_json::Value = serde_json::from_slice(&bytes[3..]).unwrap();
+        assert!(v.get("@capability_token").is_some());
+        assert_eq!(v["@body"]["id"], 1);
+    }
+
+    #[test]
+    fn serialize_rejects_capability_token_with_encryption() {
+        use crate::kafka::core::serdes::capability::{Action, Capability, CapabilityLink, CapabilityToken};
+        use p256::ecdsa::SigningKey;
+
+        let issuer_key = SigningKey::random(&mut rand::thread_rng());
+        let link = CapabilityLink::sign(
+            "did:monky:root".to_string(),
+            "did:monky:producer-a".to_string(),
+            Capability {
+                topic_pattern: "application.communication.*".to_string(),
+                action: Action::Produce,
+            },
+            0,
+            1_000_000_000,
+            &issuer_key,
+        )
+        .unwrap();
+
+        let mut mapper = HybridObjectMapper::new();
+        mapper.encryption = Some(signing::EncryptionConfig { key: [7u8; 32] });
+        let mut ser = KafkaSerializer::with_mapper(mapper);
+        ser.capability_token = Some(CapabilityToken(vec![link]));
+
+        let item = Item {
+            id: 1,
+            name: "alice".to_string(),
+            optional: None,
+        };
+        let err = ser.serialize("topic", &item).unwrap_err();
+        assert!(matches!(
+            err,
+            SerializationError::CapabilityUnsupportedWithEnvelope
+        ));
+    }
+
+    #[test]
+    fn serialize_rejects_capability_token_with_signing() {
+        use crate::kafka::core::serdes::capability::{Action, Capability, CapabilityLink, CapabilityToken};
+        use p256::ecdsa::SigningKey;
+
+        let issuer_key = SigningKey::random(&mut rand::thread_rng());
+        let link = CapabilityLink::sign(
+            "did:monky:root".to_string(),
+            "did:monky:producer-a".to_string(),
+            Capability {
+                topic_pattern: "application.communication.*".to_string(),
+                action: Action::Produce,
+            },
+            0,
+            1_000_000_000,
+            &issuer_key,
+        )
+        .unwrap();
+
+        let mut mapper = HybridObjectMapper::new();
+        mapper.signing = Some(signing::SigningConfig::Hs256 {
+            key: b"secret".to_vec(),
+        });
+        let mut ser = KafkaSerializer::with_mapper(mapper);
+        ser.capability_token = Some(CapabilityToken(vec![link]));
+
+        let item = Item {
+            id: 1,
+            name: "alice".to_string(),
+            optional: None,
+        };
+        let err = ser.serialize("topic", &item).unwrap_err();
+        assert!(matches!(
+            err,
+            SerializationError::CapabilityUnsupportedWithEnvelope
+        ));
+    }
 }
@@ -1,17 +1,200 @@
 use hmac::{Hmac, Mac};
-use sha2::Sha256;
+use sha2::{Digest as _, Sha256, Sha384, Sha512};
 use sha1::{Digest, Sha1};
 use hex;
+use once_cell::sync::Lazy;
+use std::fmt;
 
 /// Constant header name used for passing content signature in requests or responses.
 pub const CONTENT_SIGNATURE_HEADER: &str = "X-Monky-Content-Signature";
 
+/// Lowercase hex SHA-256 digest of the empty string, precomputed once.
+///
+/// Used by [`StreamingSigner`], which folds `sha256_hex("")` into every
+/// chunk signature per the `STREAMING-AWS4-HMAC-SHA256-PAYLOAD` layout.
+static EMPTY_SHA256_HEX: Lazy<String> = Lazy::new(|| sha256_hex(b""));
+
+/// Compute the lowercase hex SHA-256 digest of `data`.
+fn sha256_hex(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hex::encode(hasher.finalize())
+}
+
 /// Type alias for HMAC using SHA256 hash function.
 type HmacSha256 = Hmac<Sha256>;
 
 /// Type alias for HMAC using SHA1 hash function.
 type HmacSha1 = Hmac<Sha1>;
 
+/// Type alias for HMAC using SHA384 hash function.
+type HmacSha384 = Hmac<Sha384>;
+
+/// Type alias for HMAC using SHA512 hash function.
+type HmacSha512 = Hmac<Sha512>;
+
+/// The MAC algorithms supported by this module.
+///
+/// Used to select the underlying hash function for [`MacEngine`] and
+/// [`verify_signature`], so callers don't have to juggle raw `Hmac<D>` types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    HmacSha1,
+    HmacSha256,
+    HmacSha384,
+    HmacSha512,
+}
+
+/// A reusable MAC engine over one of the supported [`Algorithm`]s.
+///
+/// Long-lived workers that sign many Kafka records can keep one `MacEngine`
+/// around and call [`MacEngine::reset`] between messages instead of
+/// constructing a fresh `Hmac` for every record.
+pub enum MacEngine {
+    Sha1(HmacSha1),
+    Sha256(HmacSha256),
+    Sha384(HmacSha384),
+    Sha512(HmacSha512),
+}
+
+impl MacEngine {
+    /// Construct a new engine for `alg`, keyed with `key`.
+    pub fn new(alg: Algorithm, key: &[u8]) -> Result<Self, HmacError> {
+        Ok(match alg {
+            Algorithm::HmacSha1 => {
+                MacEngine::Sha1(HmacSha1::new_from_slice(key).map_err(|_| HmacError::InvalidKey)?)
+            }
+            Algorithm::HmacSha256 => MacEngine::Sha256(
+                HmacSha256::new_from_slice(key).map_err(|_| HmacError::InvalidKey)?,
+            ),
+            Algorithm::HmacSha384 => MacEngine::Sha384(
+                HmacSha384::new_from_slice(key).map_err(|_| HmacError::InvalidKey)?,
+            ),
+            Algorithm::HmacSha512 => MacEngine::Sha512(
+                HmacSha512::new_from_slice(key).map_err(|_| HmacError::InvalidKey)?,
+            ),
+        })
+    }
+
+    /// Feed more bytes into the running MAC computation.
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            MacEngine::Sha1(mac) => mac.update(data),
+            MacEngine::Sha256(mac) => mac.update(data),
+            MacEngine::Sha384(mac) => mac.update(data),
+            MacEngine::Sha512(mac) => mac.update(data),
+        }
+    }
+
+    /// Consume the engine and return the lowercase hex-encoded MAC.
+    pub fn finalize_hex(self) -> String {
+        hex::encode(self.finalize_bytes())
+    }
+
+    /// Consume the engine and return the raw MAC bytes.
+    pub fn finalize_bytes(self) -> Vec<u8> {
+        match self {
+            MacEngine::Sha1(mac) => mac.finalize().into_bytes().to_vec(),
+            MacEngine::Sha256(mac) => mac.finalize().into_bytes().to_vec(),
+            MacEngine::Sha384(mac) => mac.finalize().into_bytes().to_vec(),
+            MacEngine::Sha512(mac) => mac.finalize().into_bytes().to_vec(),
+        }
+    }
+
+    /// Consume the engine and compare the computed MAC against `expected` using
+    /// the underlying crate's constant-time `verify_slice`, rather than hex
+    /// string equality.
+    pub fn verify_slice(self, expected: &[u8]) -> bool {
+        match self {
+            MacEngine::Sha1(mac) => mac.verify_slice(expected).is_ok(),
+            MacEngine::Sha256(mac) => mac.verify_slice(expected).is_ok(),
+            MacEngine::Sha384(mac) => mac.verify_slice(expected).is_ok(),
+            MacEngine::Sha512(mac) => mac.verify_slice(expected).is_ok(),
+        }
+    }
+
+    /// Reset the engine to its just-keyed state so it can be reused for the
+    /// next message without reallocating a fresh `Hmac`.
+    pub fn reset(&mut self) {
+        match self {
+            MacEngine::Sha1(mac) => Mac::reset(mac),
+            MacEngine::Sha256(mac) => Mac::reset(mac),
+            MacEngine::Sha384(mac) => Mac::reset(mac),
+            MacEngine::Sha512(mac) => Mac::reset(mac),
+        }
+    }
+}
+
+/// Verify `expected_hex` against the MAC of `content` under `key`, using the
+/// underlying MAC's constant-time `verify_slice` instead of string equality.
+///
+/// Intended for checking headers such as [`CONTENT_SIGNATURE_HEADER`], where
+/// comparing hex strings with `==` would leak timing information about how
+/// many leading bytes matched.
+///
+/// # Errors
+///
+/// Returns `HmacError::InvalidKey` if `key` cannot key the chosen algorithm,
+/// or `HmacError::InvalidHex` if `expected_hex` is not valid hex.
+pub fn verify_signature(
+    alg: Algorithm,
+    key: &str,
+    content: &str,
+    expected_hex: &str,
+) -> Result<bool, HmacError> {
+    let expected = hex::decode(expected_hex).map_err(|_| HmacError::InvalidHex)?;
+    let mut engine = MacEngine::new(alg, key.as_bytes())?;
+    engine.update(content.as_bytes());
+    Ok(engine.verify_slice(&expected))
+}
+
+/// Incremental AWS4-style chunk signer for large or streamed Kafka record
+/// bodies, modeled on `STREAMING-AWS4-HMAC-SHA256-PAYLOAD`.
+///
+/// Each chunk's signature chains into the next: signing a chunk is
+/// `HMAC-SHA256(key, previous_signature || "\n" || sha256_hex("") || "\n" || sha256_hex(chunk))`,
+/// where `previous_signature` starts out as the caller-supplied seed
+/// signature. This lets producers sign a payload incrementally instead of
+/// buffering the whole message in memory.
+pub struct StreamingSigner {
+    key: Vec<u8>,
+    previous_signature: String,
+}
+
+impl StreamingSigner {
+    /// Seed a new signer with `key` and the initial `seed_signature` (e.g. the
+    /// signature of the request that the streamed body belongs to).
+    pub fn new(key: &str, seed_signature: &str) -> Self {
+        StreamingSigner {
+            key: key.as_bytes().to_vec(),
+            previous_signature: seed_signature.to_string(),
+        }
+    }
+
+    /// Sign the next `chunk`, chaining it onto the previous signature, and
+    /// return the lowercase hex signature for this chunk.
+    pub fn sign_chunk(&mut self, chunk: &[u8]) -> String {
+        let chunk_hash_hex = sha256_hex(chunk);
+        let string_to_sign = format!(
+            "{}\n{}\n{}",
+            self.previous_signature, *EMPTY_SHA256_HEX, chunk_hash_hex
+        );
+
+        // HMAC-SHA256 accepts any key length, so this never fails in practice.
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(string_to_sign.as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        self.previous_signature = signature.clone();
+        signature
+    }
+
+    /// Sign the zero-length terminating chunk, finishing the stream.
+    pub fn finalize(&mut self) -> String {
+        self.sign_chunk(&[])
+    }
+}
+
 /// Computes the HMAC-SHA256 of `content` using the provided `key`.
 ///
 /// # Arguments
@@ -126,18 +309,147 @@ fn get_hmac_sha1_bytes(key: &[u8], content: &[u8]) -> Result<Vec<u8>, HmacError>
 pub enum HmacError {
     /// The provided key was invalid for HMAC initialization.
     InvalidKey,
+    /// The expected signature was not valid hex.
+    InvalidHex,
 }
 
 impl std::fmt::Display for HmacError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             HmacError::InvalidKey => write!(f, "invalid HMAC key"),
+            HmacError::InvalidHex => write!(f, "expected signature is not valid hex"),
         }
     }
 }
 
 impl std::error::Error for HmacError {}
 
+/// A fixed-length digest/MAC, typed by its byte length `N`, so that values
+/// of different lengths (e.g. an HMAC-SHA1 and an HMAC-SHA256 value) can't be
+/// mixed up at the call site. Outputs of the same length, such as a SHA-1
+/// digest and an HMAC-SHA1 value, share one type (`Signature<20>`).
+///
+/// `PartialEq` performs a constant-time byte comparison, so comparing two
+/// `Signature`s is timing-safe by construction, unlike comparing the raw hex
+/// strings with `==`.
+#[derive(Clone, Copy)]
+pub struct Signature<const N: usize>(pub [u8; N]);
+
+/// A 20-byte digest/MAC, e.g. the output of SHA-1 or HMAC-SHA1.
+pub type Sha1Signature = Signature<20>;
+
+/// A 32-byte digest/MAC, e.g. the output of HMAC-SHA256.
+pub type Sha256Signature = Signature<32>;
+
+impl<const N: usize> Signature<N> {
+    /// Wrap raw bytes that are already known to be `N` long.
+    pub fn from_bytes(bytes: [u8; N]) -> Self {
+        Signature(bytes)
+    }
+
+    /// Borrow the underlying bytes.
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> PartialEq for Signature<N> {
+    fn eq(&self, other: &Self) -> bool {
+        // Accumulate the XOR of every byte pair so that no early return
+        // leaks how many leading bytes matched.
+        let mut diff = 0u8;
+        for (a, b) in self.0.iter().zip(other.0.iter()) {
+            diff |= a ^ b;
+        }
+        diff == 0
+    }
+}
+
+impl<const N: usize> Eq for Signature<N> {}
+
+impl<const N: usize> fmt::LowerHex for Signature<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> fmt::Display for Signature<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl<const N: usize> fmt::Debug for Signature<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Signature({})", self)
+    }
+}
+
+impl<const N: usize> std::str::FromStr for Signature<N> {
+    type Err = SignatureParseError;
+
+    /// Parse a lowercase hex string into a `Signature<N>`, erroring if its
+    /// length doesn't match `2 * N` hex digits or it contains invalid
+    /// nibbles.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != N * 2 {
+            return Err(SignatureParseError::WrongLength {
+                expected: N * 2,
+                found: s.len(),
+            });
+        }
+        let decoded = hex::decode(s).map_err(|_| SignatureParseError::InvalidHex)?;
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(&decoded);
+        Ok(Signature(bytes))
+    }
+}
+
+/// Errors from parsing a [`Signature`] out of a hex string.
+#[derive(Debug)]
+pub enum SignatureParseError {
+    /// The string was not exactly `2 * N` hex characters long.
+    WrongLength { expected: usize, found: usize },
+    /// The string contained a non-hex character.
+    InvalidHex,
+}
+
+impl fmt::Display for SignatureParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureParseError::WrongLength { expected, found } => write!(
+                f,
+                "wrong signature length: expected {} hex chars, found {}",
+                expected, found
+            ),
+            SignatureParseError::InvalidHex => write!(f, "invalid hex in signature"),
+        }
+    }
+}
+
+impl std::error::Error for SignatureParseError {}
+
+/// Like [`get_signature`], but returns a typed, timing-safe-comparable
+/// [`Sha256Signature`] instead of a bare hex `String`.
+pub fn get_signature_typed(key: &str, content: &str) -> Result<Sha256Signature, HmacError> {
+    let bytes = get_hmac_sha256_bytes(key.as_bytes(), content.as_bytes())?;
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&bytes);
+    Ok(Signature(out))
+}
+
+/// Like [`get_hmac`], but returns a typed, timing-safe-comparable
+/// [`Sha1Signature`] instead of a bare hex `String`.
+pub fn get_hmac_typed(key: &str, content: &str) -> Result<Sha1Signature, HmacError> {
+    let bytes = get_hmac_sha1_bytes(key.as_bytes(), content.as_bytes())?;
+    let mut out = [0u8; 20];
+    out.copy_from_slice(&bytes);
+    Ok(Signature(out))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,5 +516,119 @@ mod tests {
         assert!(result.is_ok());
         assert!(!result.unwrap().is_empty());
     }
+
+    #[test]
+    fn test_verify_signature_valid() {
+        let key = "secretkey";
+        let content = "Hello, world!";
+        let expected = compute_expected_hmac_sha256(key.as_bytes(), content.as_bytes());
+        assert!(verify_signature(Algorithm::HmacSha256, key, content, &expected).unwrap());
+    }
+
+    #[test]
+    fn test_verify_signature_rejects_tampered_content() {
+        let key = "secretkey";
+        let expected = compute_expected_hmac_sha256(key.as_bytes(), b"original");
+        assert!(!verify_signature(Algorithm::HmacSha256, key, "tampered", &expected).unwrap());
+    }
+
+    #[test]
+    fn test_verify_signature_invalid_hex() {
+        let result = verify_signature(Algorithm::HmacSha256, "key", "content", "not-hex");
+        assert!(matches!(result, Err(HmacError::InvalidHex)));
+    }
+
+    #[test]
+    fn test_mac_engine_matches_one_shot_helpers() {
+        let key = b"mykey";
+        let content = b"some content";
+
+        let mut engine = MacEngine::new(Algorithm::HmacSha1, key).unwrap();
+        engine.update(content);
+        let hex_out = engine.finalize_hex();
+
+        assert_eq!(hex_out, compute_expected_hmac_sha1(key, content));
+    }
+
+    #[test]
+    fn test_mac_engine_reset_reuses_state() {
+        let mut engine = MacEngine::new(Algorithm::HmacSha256, b"key").unwrap();
+        engine.update(b"first message");
+        engine.reset();
+        engine.update(b"second message");
+        let hex_out = engine.finalize_hex();
+
+        assert_eq!(hex_out, compute_expected_hmac_sha256(b"key", b"second message"));
+    }
+
+    #[test]
+    fn test_streaming_signer_chains_signatures() {
+        let mut signer = StreamingSigner::new("key", "seed-signature");
+
+        let first = signer.sign_chunk(b"first chunk of data");
+        let second = signer.sign_chunk(b"second chunk of data");
+        let last = signer.finalize();
+
+        // Each chunk signature should differ and the chain should not repeat itself.
+        assert_ne!(first, second);
+        assert_ne!(second, last);
+
+        // Re-deriving the first chunk's signature by hand should match.
+        let empty_hash = sha256_hex(b"");
+        let chunk_hash = sha256_hex(b"first chunk of data");
+        let string_to_sign = format!("seed-signature\n{}\n{}", empty_hash, chunk_hash);
+        let expected = compute_expected_hmac_sha256(b"key", string_to_sign.as_bytes());
+        assert_eq!(first, expected);
+    }
+
+    #[test]
+    fn test_streaming_signer_is_deterministic() {
+        let mut signer_a = StreamingSigner::new("key", "seed");
+        let mut signer_b = StreamingSigner::new("key", "seed");
+
+        assert_eq!(signer_a.sign_chunk(b"chunk"), signer_b.sign_chunk(b"chunk"));
+        assert_eq!(signer_a.finalize(), signer_b.finalize());
+    }
+
+    #[test]
+    fn test_get_signature_typed_matches_hex_helper() {
+        let key = "secretkey";
+        let content = "Hello, world!";
+        let hex_sig = get_signature(key, content).unwrap();
+        let typed_sig = get_signature_typed(key, content).unwrap();
+        assert_eq!(typed_sig.to_string(), hex_sig);
+    }
+
+    #[test]
+    fn test_signature_from_str_round_trips() {
+        let hex_sig = get_signature("key", "content").unwrap();
+        let parsed: Sha256Signature = hex_sig.parse().unwrap();
+        assert_eq!(parsed.to_string(), hex_sig);
+    }
+
+    #[test]
+    fn test_signature_from_str_rejects_wrong_length() {
+        let result = "abcd".parse::<Sha256Signature>();
+        assert!(matches!(
+            result,
+            Err(SignatureParseError::WrongLength { expected: 64, found: 4 })
+        ));
+    }
+
+    #[test]
+    fn test_signature_from_str_rejects_bad_hex() {
+        let bad = "zz".repeat(20); // 40 chars, right length for Sha1Signature, invalid nibbles
+        let result = bad.parse::<Sha1Signature>();
+        assert!(matches!(result, Err(SignatureParseError::InvalidHex)));
+    }
+
+    #[test]
+    fn test_signature_equality_is_value_based() {
+        let a: Sha256Signature = get_signature("key", "content").unwrap().parse().unwrap();
+        let b: Sha256Signature = get_signature("key", "content").unwrap().parse().unwrap();
+        let c: Sha256Signature = get_signature("key", "other content").unwrap().parse().unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
 }
 
@@ -92,6 +92,142 @@ pub fn parse_url_encoded(
     }
 }
 
+/// A structured value parsed from `application/x-www-form-urlencoded`
+/// payloads that use bracketed array/nested notation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UrlValue {
+    /// A plain `key=value` pair with no repetition or brackets seen.
+    Single(String),
+    /// Built from repeated keys (`k=a&k=b`) or trailing `[]` brackets
+    /// (`tags[]=a&tags[]=b`), in encounter order.
+    List(Vec<String>),
+    /// Built from `key[sub]`/`key[sub][sub2]` brackets, nested by sub-key.
+    Map(HashMap<String, UrlValue>),
+}
+
+/// Splits a raw form key like `user[name][first]` into its top-level key
+/// (`"user"`) and bracket segments (`["name", "first"]`). A trailing `[]`
+/// yields an empty-string segment. A key with no `[` returns no segments.
+fn split_key_path(raw_key: &str) -> (String, Vec<String>) {
+    let Some(first_bracket) = raw_key.find('[') else {
+        return (raw_key.to_string(), Vec::new());
+    };
+
+    let top_key = raw_key[..first_bracket].to_string();
+    let mut segments = Vec::new();
+    let mut segment_start: Option<usize> = None;
+    for (i, c) in raw_key[first_bracket..].char_indices() {
+        match c {
+            '[' => segment_start = Some(i + 1),
+            ']' => {
+                if let Some(start) = segment_start.take() {
+                    segments.push(raw_key[first_bracket..][start..i].to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    (top_key, segments)
+}
+
+/// Merges `value` into `map[key]` under plain-key (no brackets) or
+/// trailing-`[]` semantics: first occurrence becomes `Single`/`List`,
+/// a later occurrence of the same key promotes `Single` to `List`. A `Map`
+/// already stored under `key` takes precedence and the value is dropped.
+fn merge_scalar_or_list(map: &mut HashMap<String, UrlValue>, key: String, value: String, as_list: bool) {
+    match map.get_mut(&key) {
+        None => {
+            let fresh = if as_list {
+                UrlValue::List(vec![value])
+            } else {
+                UrlValue::Single(value)
+            };
+            map.insert(key, fresh);
+        }
+        Some(UrlValue::Single(existing)) => {
+            let promoted = UrlValue::List(vec![existing.clone(), value]);
+            map.insert(key, promoted);
+        }
+        Some(UrlValue::List(list)) => list.push(value),
+        Some(UrlValue::Map(_)) => {}
+    }
+}
+
+/// Inserts `value` at `key` (with any remaining bracket `segments`) into
+/// `map`, recursing into/creating nested `Map`s for named segments and
+/// falling back to `merge_scalar_or_list` once segments are exhausted.
+fn insert_path(map: &mut HashMap<String, UrlValue>, key: String, segments: &[String], value: String) {
+    let Some((head, rest)) = segments.split_first() else {
+        merge_scalar_or_list(map, key, value, false);
+        return;
+    };
+
+    if head.is_empty() {
+        // Trailing `[]`: list semantics. Any further segments after `[]`
+        // aren't a notation this parser supports, so they're ignored.
+        merge_scalar_or_list(map, key, value, true);
+        return;
+    }
+
+    match map.entry(key).or_insert_with(|| UrlValue::Map(HashMap::new())) {
+        UrlValue::Map(inner) => insert_path(inner, head.clone(), rest, value),
+        // A scalar/list was already stored under this key; a bracketed
+        // path takes precedence, so replace it with a fresh nested map.
+        existing @ (UrlValue::Single(_) | UrlValue::List(_)) => {
+            let mut inner = HashMap::new();
+            insert_path(&mut inner, head.clone(), rest, value);
+            *existing = UrlValue::Map(inner);
+        }
+    }
+}
+
+/// Parse an `application/x-www-form-urlencoded` payload into a
+/// structure-aware `HashMap<String, UrlValue>`, preserving repeated keys and
+/// bracketed array/nested notation that `parse_url_encoded` would otherwise
+/// flatten or comma-join.
+///
+/// - A trailing `[]` segment (`tags[]=a&tags[]=b`) builds a `List`.
+/// - A named bracket segment (`user[name]=x`, `user[name][first]=x`) builds
+///   nested `Map`s, parsing the bracket path left-to-right and merging into
+///   the tree.
+/// - A plain key with no brackets builds a `Single`, unless the same key is
+///   seen again, in which case it's promoted to a `List`.
+///
+/// Conflict rule: once a key resolves to a `Map` (because a bracketed path
+/// was seen for it), a bracketed path always wins over an existing
+/// scalar/list for the same key; conversely, a later plain/`[]` value for a
+/// key that already resolved to a `Map` is dropped rather than overwriting it.
+///
+/// # Examples
+///
+/// ```
+/// use monky_utilities::url_parse::{parse_url_encoded_structured, UrlValue};
+///
+/// let payload = "tags[]=a&tags[]=b&user[name]=alice&user[age]=30&plain=x";
+/// let parsed = parse_url_encoded_structured(payload);
+///
+/// assert_eq!(
+///     parsed.get("tags"),
+///     Some(&UrlValue::List(vec!["a".to_string(), "b".to_string()]))
+/// );
+/// assert_eq!(parsed.get("plain"), Some(&UrlValue::Single("x".to_string())));
+///
+/// let user = match parsed.get("user") {
+///     Some(UrlValue::Map(m)) => m,
+///     other => panic!("expected a map, got {:?}", other),
+/// };
+/// assert_eq!(user.get("name"), Some(&UrlValue::Single("alice".to_string())));
+/// assert_eq!(user.get("age"), Some(&UrlValue::Single("30".to_string())));
+/// ```
+pub fn parse_url_encoded_structured(payload: &str) -> HashMap<String, UrlValue> {
+    let mut result: HashMap<String, UrlValue> = HashMap::with_capacity(8);
+    for (raw_key, value) in form_urlencoded::parse(payload.as_bytes()) {
+        let (top_key, segments) = split_key_path(&raw_key);
+        insert_path(&mut result, top_key, &segments, value.into_owned());
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,4 +280,106 @@ mod tests {
         assert_eq!(m.get("k").map(|s| s.as_str()), Some("/path/to/file"));
         assert_eq!(m.get("space").map(|s| s.as_str()), Some("one two"));
     }
+
+    #[test]
+    fn structured_plain_key_is_single() {
+        let parsed = parse_url_encoded_structured("name=alice");
+        assert_eq!(
+            parsed.get("name"),
+            Some(&UrlValue::Single("alice".to_string()))
+        );
+    }
+
+    #[test]
+    fn structured_repeated_plain_key_promotes_to_list() {
+        let parsed = parse_url_encoded_structured("k=a&k=b&k=c");
+        assert_eq!(
+            parsed.get("k"),
+            Some(&UrlValue::List(vec![
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string()
+            ]))
+        );
+    }
+
+    #[test]
+    fn structured_trailing_brackets_build_list() {
+        let parsed = parse_url_encoded_structured("tags[]=a&tags[]=b");
+        assert_eq!(
+            parsed.get("tags"),
+            Some(&UrlValue::List(vec!["a".to_string(), "b".to_string()]))
+        );
+    }
+
+    #[test]
+    fn structured_named_bracket_builds_nested_map() {
+        let parsed = parse_url_encoded_structured("user[name]=alice&user[age]=30");
+        let user = match parsed.get("user") {
+            Some(UrlValue::Map(m)) => m,
+            other => panic!("expected a map, got {:?}", other),
+        };
+        assert_eq!(
+            user.get("name"),
+            Some(&UrlValue::Single("alice".to_string()))
+        );
+        assert_eq!(user.get("age"), Some(&UrlValue::Single("30".to_string())));
+    }
+
+    #[test]
+    fn structured_doubly_nested_bracket_path() {
+        let parsed = parse_url_encoded_structured("user[name][first]=alice&user[name][last]=smith");
+        let user = match parsed.get("user") {
+            Some(UrlValue::Map(m)) => m,
+            other => panic!("expected a map, got {:?}", other),
+        };
+        let name = match user.get("name") {
+            Some(UrlValue::Map(m)) => m,
+            other => panic!("expected a nested map, got {:?}", other),
+        };
+        assert_eq!(
+            name.get("first"),
+            Some(&UrlValue::Single("alice".to_string()))
+        );
+        assert_eq!(
+            name.get("last"),
+            Some(&UrlValue::Single("smith".to_string()))
+        );
+    }
+
+    #[test]
+    fn structured_bracket_path_wins_over_earlier_scalar() {
+        // `user` seen first as a plain scalar, then as a bracketed path —
+        // the bracketed path should win and replace it with a map.
+        let parsed = parse_url_encoded_structured("user=flat&user[name]=alice");
+        assert!(matches!(parsed.get("user"), Some(UrlValue::Map(_))));
+    }
+
+    #[test]
+    fn structured_scalar_after_map_is_dropped() {
+        // Once `user` resolves to a map, a later plain `user=...` for the
+        // same key must not clobber it.
+        let parsed = parse_url_encoded_structured("user[name]=alice&user=flat");
+        let user = match parsed.get("user") {
+            Some(UrlValue::Map(m)) => m,
+            other => panic!("expected a map, got {:?}", other),
+        };
+        assert_eq!(
+            user.get("name"),
+            Some(&UrlValue::Single("alice".to_string()))
+        );
+    }
+
+    #[test]
+    fn structured_mixed_payload() {
+        let payload = "tags[]=a&tags[]=b&user[name]=alice&user[age]=30&plain=x";
+        let parsed = parse_url_encoded_structured(payload);
+
+        assert_eq!(
+            parsed.get("tags"),
+            Some(&UrlValue::List(vec!["a".to_string(), "b".to_string()]))
+        );
+        assert_eq!(parsed.get("plain"), Some(&UrlValue::Single("x".to_string())));
+        assert!(matches!(parsed.get("user"), Some(UrlValue::Map(_))));
+    }
 }
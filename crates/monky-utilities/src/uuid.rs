@@ -15,23 +15,38 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-//! UUID v5 utilities — RFC-4122 namespace-based UUID v5 helpers and utilities.
+//! UUID v3/v5/v7 utilities — RFC-4122 namespace-based UUID v3 (MD5) and v5
+//! (SHA-1) helpers, plus RFC 9562 time-ordered UUID v7 generation.
 //!
 //! This module provides:
 //! - `from_bytes([u8; 16]) -> Uuid` — set version/variant and construct a UUID.
 //! - `from_name(name: &str) -> Uuid` — SHA-1(name) -> first 16 bytes -> UUID v5 (name-only).
 //! - `from_namespace_and_name(namespace: &Uuid, name: &str) -> Uuid` — RFC-4122 correct.
 //! - `from_reader<R: Read>(reader: &mut R) -> Result<Uuid, io::Error>` — stream SHA-1 over reader.
+//! - `uuid_v3_from_name(name: &str) -> Uuid` — MD5(name) -> first 16 bytes -> UUID v3 (name-only).
+//! - `uuid_v3_from_namespace_and_name(namespace: &Uuid, name: &str) -> Uuid` — RFC-4122 correct.
+//! - `uuid_v7_from_millis(epoch_millis: i128) -> Result<Uuid, DateFormatError>` — timestamp-sortable UUID v7.
+//! - `uuid_v7_now() -> Uuid` — `uuid_v7_from_millis` at the current instant.
 
+use md5::Md5;
+use rand::RngCore;
 use sha1::Digest;
 use sha1::Sha1;
 use std::io::{self, Read};
+use time::OffsetDateTime;
 use uuid::Uuid;
 
-/// Set the version (5) and variant (RFC 4122 / IETF) bits on a 16-byte array and construct a `Uuid`.
-pub fn uuid_from_bytes(mut bytes: [u8; 16]) -> Uuid {
-    // Clear version nibble and set to 5 (0101)
-    bytes[6] = (bytes[6] & 0x0f) | (5u8 << 4); // 0x50
+use crate::date_format::DateFormatError;
+
+/// Largest Unix millisecond timestamp that fits in the 48-bit field used by
+/// UUID v7's timestamp (bytes 0..6).
+const MAX_V7_TIMESTAMP_MILLIS: i128 = 0xFFFF_FFFF_FFFF;
+
+/// Set the variant (RFC 4122 / IETF) bits on a 16-byte array and stamp the
+/// given `version` (1..=8) into the version nibble, then construct a `Uuid`.
+fn set_version_and_variant(mut bytes: [u8; 16], version: u8) -> Uuid {
+    // Clear version nibble and set to `version`
+    bytes[6] = (bytes[6] & 0x0f) | (version << 4);
 
     // Set the variant to RFC 4122 (10xx_xxxx)
     bytes[8] = (bytes[8] & 0x3f) | 0x80;
@@ -39,6 +54,53 @@ pub fn uuid_from_bytes(mut bytes: [u8; 16]) -> Uuid {
     Uuid::from_bytes(bytes)
 }
 
+/// Set the version (5) and variant (RFC 4122 / IETF) bits on a 16-byte array and construct a `Uuid`.
+pub fn uuid_from_bytes(bytes: [u8; 16]) -> Uuid {
+    set_version_and_variant(bytes, 5)
+}
+
+/// Build a time-ordered UUID v7 (RFC 9562) from a Unix millisecond timestamp.
+///
+/// Layout: bytes 0..6 hold the 48-bit timestamp in big-endian order; the
+/// remaining 10 bytes (version/variant nibbles aside) are filled from a
+/// CSPRNG, reusing [`set_version_and_variant`] to stamp version 7 and the
+/// RFC-4122 variant.
+///
+/// # Errors
+///
+/// Returns `DateFormatError::InvalidTimestamp` if `epoch_millis` is negative
+/// or does not fit in 48 bits.
+pub fn uuid_v7_from_millis(epoch_millis: i128) -> Result<Uuid, DateFormatError> {
+    if epoch_millis < 0 || epoch_millis > MAX_V7_TIMESTAMP_MILLIS {
+        return Err(DateFormatError::InvalidTimestamp);
+    }
+    let millis = epoch_millis as u64;
+
+    let mut bytes = [0u8; 16];
+    bytes[0] = (millis >> 40) as u8;
+    bytes[1] = (millis >> 32) as u8;
+    bytes[2] = (millis >> 24) as u8;
+    bytes[3] = (millis >> 16) as u8;
+    bytes[4] = (millis >> 8) as u8;
+    bytes[5] = millis as u8;
+    rand::thread_rng().fill_bytes(&mut bytes[6..]);
+
+    Ok(set_version_and_variant(bytes, 7))
+}
+
+/// Convenience wrapper over `uuid_v7_from_millis` using the current instant
+/// (`OffsetDateTime::now_utc()`), matching this crate's `date_format`
+/// epoch-millis convention.
+///
+/// # Panics
+///
+/// Panics if the current time does not fit in 48-bit milliseconds, which
+/// cannot happen for any realistic system clock.
+pub fn uuid_v7_now() -> Uuid {
+    let millis = OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000;
+    uuid_v7_from_millis(millis).expect("current time fits in the 48-bit UUID v7 timestamp field")
+}
+
 /// Compute UUID v5 from `name` bytes (NOT namespace-aware).
 /// It computes SHA-1(name) and uses the first 16 bytes to build the UUID v5.
 ///
@@ -70,6 +132,37 @@ pub fn uuid_from_namespace_and_name(namespace: &Uuid, name: &str) -> Uuid {
     uuid_from_bytes(bytes)
 }
 
+/// Compute UUID v3 from `name` bytes (NOT namespace-aware).
+/// It computes MD5(name) and uses the resulting 16-byte digest to build the UUID v3.
+///
+/// Note: RFC-4122 specifies v3 as MD5(namespace || name). If you want the RFC behavior,
+/// use `uuid_v3_from_namespace_and_name(namespace, name)`.
+pub fn uuid_v3_from_name(name: &str) -> Uuid {
+    let mut hasher = Md5::new();
+    hasher.update(name.as_bytes());
+    let digest = hasher.finalize(); // 16 bytes
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest);
+    set_version_and_variant(bytes, 3)
+}
+
+/// Compute UUID v3 according to RFC-4122: MD5(namespace_bytes || name_bytes).
+///
+/// `namespace` is a UUID (for example, `uuid::Uuid::NAMESPACE_DNS`), `name` is the name string.
+/// Returns a UUID v3.
+pub fn uuid_v3_from_namespace_and_name(namespace: &Uuid, name: &str) -> Uuid {
+    let mut hasher = Md5::new();
+
+    // namespace as 16 bytes in network (big-endian) order
+    hasher.update(namespace.as_bytes());
+    hasher.update(name.as_bytes());
+
+    let digest = hasher.finalize(); // 16 bytes
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest);
+    set_version_and_variant(bytes, 3)
+}
+
 /// Compute SHA-1 over a reader (streaming) and return uuid v5 from resulting digest.
 ///
 /// The reader is consumed (read until EOF). If you need to reuse the stream, caller must
@@ -133,6 +226,31 @@ mod tests {
         assert_eq!(uuid.get_variant(), uuid::Variant::RFC4122);
     }
 
+    #[test]
+    fn test_uuid_v3_from_name_non_namespace_consistent() {
+        let name = "test name";
+        let uuid1 = uuid_v3_from_name(name);
+        let uuid2 = uuid_v3_from_name(name);
+        assert_eq!(uuid1, uuid2);
+
+        assert_eq!(uuid1.get_version_num(), 3);
+        assert_eq!(uuid1.get_variant(), uuid::Variant::RFC4122);
+    }
+
+    #[test]
+    fn test_uuid_v3_from_namespace_and_name_matches_rfc() {
+        let namespace = Uuid::NAMESPACE_DNS;
+        let name = "example.com";
+        let uuid = uuid_v3_from_namespace_and_name(&namespace, name);
+
+        // The result should be the same as the standard uuid crate's v3 generation
+        let expected = Uuid::new_v3(&namespace, name.as_bytes());
+
+        assert_eq!(uuid, expected);
+        assert_eq!(uuid.get_version_num(), 3);
+        assert_eq!(uuid.get_variant(), uuid::Variant::RFC4122);
+    }
+
     #[test]
     fn test_uuid_from_reader_correctness() {
         let data = b"some test input for sha1";
@@ -149,4 +267,54 @@ mod tests {
             uuid_from_reader(&mut cursor2).expect("Failed to create UUID from second reader");
         assert_eq!(uuid, uuid2);
     }
+
+    #[test]
+    fn test_uuid_v7_from_millis_sets_version_and_variant() {
+        let uuid = uuid_v7_from_millis(1_725_000_000_123).unwrap();
+
+        assert_eq!(uuid.get_version_num(), 7);
+        assert_eq!(uuid.get_variant(), uuid::Variant::RFC4122);
+    }
+
+    #[test]
+    fn test_uuid_v7_from_millis_encodes_timestamp_prefix() {
+        let epoch_millis = 1_725_000_000_123i128;
+        let uuid = uuid_v7_from_millis(epoch_millis).unwrap();
+
+        let bytes = uuid.as_bytes();
+        let mut ts_bytes = [0u8; 8];
+        ts_bytes[2..].copy_from_slice(&bytes[0..6]);
+        let decoded_millis = u64::from_be_bytes(ts_bytes);
+
+        assert_eq!(decoded_millis as i128, epoch_millis);
+    }
+
+    #[test]
+    fn test_uuid_v7_from_millis_rejects_negative_and_oversized() {
+        assert!(matches!(
+            uuid_v7_from_millis(-1),
+            Err(DateFormatError::InvalidTimestamp)
+        ));
+        assert!(matches!(
+            uuid_v7_from_millis(MAX_V7_TIMESTAMP_MILLIS + 1),
+            Err(DateFormatError::InvalidTimestamp)
+        ));
+        assert!(uuid_v7_from_millis(MAX_V7_TIMESTAMP_MILLIS).is_ok());
+    }
+
+    #[test]
+    fn test_uuid_v7_from_millis_is_monotonically_sortable_across_timestamps() {
+        let earlier = uuid_v7_from_millis(1_000).unwrap();
+        let later = uuid_v7_from_millis(2_000).unwrap();
+
+        assert!(earlier < later);
+    }
+
+    #[test]
+    fn test_uuid_v7_now_has_version_and_variant() {
+        let uuid = uuid_v7_now();
+
+        assert_eq!(uuid.get_version_num(), 7);
+        assert_eq!(uuid.get_variant(), uuid::Variant::RFC4122);
+    }
 }